@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::fmt::Write;
 use std::fs::File;
 use std::io::BufReader;
@@ -7,6 +8,15 @@ use std::{env, fs, io};
 use tar::Archive;
 use zstd::stream::Decoder;
 
+#[path = "src/coverage.rs"]
+mod coverage;
+#[path = "build/meta.rs"]
+mod meta;
+#[path = "build/selection.rs"]
+mod selection;
+#[path = "build/subset.rs"]
+mod subset;
+
 fn main() -> anyhow::Result<()> {
     let out_dir = env::var("OUT_DIR")?;
     let out_dir = Path::new(&out_dir);
@@ -27,6 +37,11 @@ fn unpack_fonts(archive_path: impl AsRef<Path>, output_dir: impl AsRef<Path>) ->
     let reader = BufReader::new(file);
     let decoder = Decoder::new(reader)?;
     let mut archive = Archive::new(decoder);
+    // files of an earlier archive, like its license texts, must not outlive it
+    match fs::remove_dir_all(&output_dir) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => (),
+    }
     archive.unpack(output_dir)?;
     Ok(())
 }
@@ -38,23 +53,45 @@ fn build_fonts_module(
     const TEMPLATE: &str = include_str!("./templates/fonts.template.rs");
     const PLACEHOLDER_NOTO_SANS: &str = "// __PLACEHOLDER_NOTO_SANS__";
     const PLACEHOLDER_NOTO_SERIF: &str = "// __PLACEHOLDER_NOTO_SERIF__";
+    const PLACEHOLDER_NOTO_MONO: &str = "// __PLACEHOLDER_NOTO_MONO__";
     const PLACEHOLDER_NOTO_REST: &str = "// __PLACEHOLDER_NOTO_REST__";
+    const PLACEHOLDER_FONT_INFOS: &str = "// __PLACEHOLDER_FONT_INFOS__";
+    const PLACEHOLDER_SUBSET: &str = "// __PLACEHOLDER_SUBSET__";
+    const PLACEHOLDER_LICENSE_TEXTS: &str = "// __PLACEHOLDER_LICENSE_TEXTS__";
 
     let mut noto_sans = vec![];
     let mut noto_serif = vec![];
+    let mut noto_mono = vec![];
     let mut noto_rest = vec![];
 
-    let fonts_dir = fs::read_dir(fonts_dir)?;
-    for font_file in fonts_dir {
+    let fonts_dir = fonts_dir.as_ref();
+    let licenses_dir = fonts_dir.join("licenses");
+    if !licenses_dir.is_dir() {
+        println!(
+            "cargo:warning=fonts.tar.zst contains no license files, the licenses described by the \
+             fonts are used instead, re-run `cargo run -p package` to bundle them"
+        );
+    }
+    for font_file in fs::read_dir(fonts_dir)? {
         let font_file = font_file?;
         let file_name = font_file.file_name().to_string_lossy().to_string();
         match file_name.as_str() {
+            _ if !file_name.ends_with(".otf") => (),
             _ if file_name.contains("Test") => (),
+            _ if file_name.contains("Mono") => noto_mono.push(file_name),
             _ if file_name.contains("NotoSans") => noto_sans.push(file_name),
             _ if file_name.contains("NotoSerif") => noto_serif.push(file_name),
             _ => noto_rest.push(file_name),
         }
     }
+    for list in [
+        &mut noto_sans,
+        &mut noto_serif,
+        &mut noto_mono,
+        &mut noto_rest,
+    ] {
+        list.sort();
+    }
 
     fn make_macro(name: &str, list: &[String]) -> anyhow::Result<String> {
         let mut out = String::new();
@@ -66,11 +103,109 @@ fn build_fonts_module(
         Ok(out)
     }
 
+    let selection = selection::font_selection()?;
+    let subset = subset::subset_selection()?;
+    let mut unmatched: BTreeSet<&str> = selection.iter().flatten().map(String::as_str).collect();
+    let mut font_infos = vec![];
+    let mut license_texts: Vec<String> = vec![];
+    for (group, list) in [
+        ("Sans", &mut noto_sans),
+        ("Serif", &mut noto_serif),
+        ("Mono", &mut noto_mono),
+        ("Rest", &mut noto_rest),
+    ] {
+        let mut fonts = vec![];
+        for file_name in list.iter() {
+            let path = fonts_dir.join(file_name);
+            let data = fs::read(&path)?;
+            let mut font = meta::FontMeta::read(&data, file_name)?;
+            let license_path = licenses_dir.join(Path::new(file_name).with_extension("txt"));
+            match fs::read_to_string(&license_path) {
+                Ok(license_text) => font.license_text = license_text,
+                Err(_) if !licenses_dir.is_dir() => (),
+                Err(_) => println!(
+                    "cargo:warning=no license file {}, using the license described by {file_name}",
+                    license_path.display()
+                ),
+            }
+            if let Some(selection) = &selection {
+                let matching: Vec<_> = selection
+                    .iter()
+                    .filter(|entry| font.matches(entry))
+                    .collect();
+                if matching.is_empty() {
+                    continue;
+                }
+                matching
+                    .into_iter()
+                    .for_each(|entry| _ = unmatched.remove(entry.as_str()));
+            }
+            if let Some(subset) = &subset {
+                match subset::subset_font(&data, subset) {
+                    Some(data) => {
+                        fs::write(&path, &data)?;
+                        let license_text = font.license_text;
+                        font = meta::FontMeta::read(&data, file_name)?;
+                        font.license_text = license_text;
+                        font.subset = true;
+                    }
+                    None => {
+                        println!("cargo:warning=could not subset {file_name}, it is embedded fully")
+                    }
+                }
+            }
+            fonts.push(font);
+        }
+        list.retain(|file_name| fonts.iter().any(|font| &font.file_name == file_name));
+        for (index, font) in fonts.iter().enumerate() {
+            let license_text = match license_texts
+                .iter()
+                .position(|text| *text == font.license_text)
+            {
+                Some(position) => position,
+                None => {
+                    license_texts.push(font.license_text.clone());
+                    license_texts.len() - 1
+                }
+            };
+            font_infos.push(font.make_font_info(group, index, license_text)?);
+        }
+    }
+    if !unmatched.is_empty() {
+        anyhow::bail!("no bundled font matches the selected fonts {unmatched:?}");
+    }
+    let font_infos = format!(
+        "static FONT_INFOS: [FontInfo; {}] = [{}\n];",
+        font_infos.len(),
+        font_infos.concat()
+    );
+    let subset = match subset {
+        Some(mut ranges) => {
+            ranges.sort_by_key(|range| *range.start());
+            let ranges: Vec<_> = ranges
+                .iter()
+                .map(|range| format!("{:?}..={:?}", range.start(), range.end()))
+                .collect();
+            format!("Some(&[{}])", ranges.join(", "))
+        }
+        None => "None".to_string(),
+    };
+    let license_texts = format!(
+        "const LICENSE_TEXTS: [&str; {}] = {:?};",
+        license_texts.len(),
+        license_texts
+    );
+    let subset = format!("pub static SUBSET: Option<&[RangeInclusive<char>]> = {subset};");
+
     #[rustfmt::skip]
     let file = TEMPLATE
         .replace(PLACEHOLDER_NOTO_SANS, &make_macro("NOTO_SANS", &noto_sans)?)
         .replace(PLACEHOLDER_NOTO_SERIF, &make_macro("NOTO_SERIF", &noto_serif)?)
-        .replace( PLACEHOLDER_NOTO_REST, &make_macro("NOTO_REST", &noto_rest)?);
+        .replace( PLACEHOLDER_NOTO_MONO, &make_macro("NOTO_MONO", &noto_mono)?)
+        .replace( PLACEHOLDER_NOTO_REST, &make_macro("NOTO_REST", &noto_rest)?)
+        .replace(PLACEHOLDER_FONT_INFOS, &font_infos)
+        .replace(PLACEHOLDER_SUBSET, &subset)
+        .replace(PLACEHOLDER_LICENSE_TEXTS, &license_texts);
 
     fs::write(module_path, file)?;
     Ok(())
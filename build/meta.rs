@@ -0,0 +1,153 @@
+//! Build-time font metadata, see [`FontMeta`].
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+use ttf_parser::{Face, Language, name_id};
+use unicode_script::{Script, UnicodeScript};
+
+use crate::coverage::{char_ranges, cmap_chars};
+
+/// Metadata of a font file, read at build time.
+pub struct FontMeta {
+    pub file_name: String,
+    pub family: String,
+    pub scripts: Vec<Script>,
+    pub chars: BTreeSet<char>,
+    pub style: &'static str,
+    pub weight: u16,
+    pub license: &'static str,
+    pub copyright: String,
+    pub license_text: String,
+    pub subset: bool,
+}
+
+impl FontMeta {
+    pub fn read(data: &[u8], file_name: &str) -> anyhow::Result<Self> {
+        let face = Face::parse(data, 0)?;
+        let family = name(&face, name_id::TYPOGRAPHIC_FAMILY)
+            .or_else(|| name(&face, name_id::FAMILY))
+            .unwrap_or_else(|| file_name.to_string());
+        let license = match name(&face, name_id::LICENSE) {
+            Some(license) if license.contains("Open Font License") => "OFL-1.1",
+            _ => "unknown",
+        };
+        let copyright = name(&face, name_id::COPYRIGHT_NOTICE).unwrap_or_default();
+        // fonts without a bundled license file at least describe their license themselves
+        let license_text = [name_id::LICENSE, name_id::LICENSE_URL]
+            .into_iter()
+            .filter_map(|name_id| name(&face, name_id))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let style = match face.style() {
+            ttf_parser::Style::Normal => "Normal",
+            ttf_parser::Style::Italic => "Italic",
+            ttf_parser::Style::Oblique => "Oblique",
+        };
+
+        let chars = cmap_chars(&face);
+        let mut script_counts: HashMap<Script, usize> = HashMap::new();
+        for &c in &chars {
+            match c.script() {
+                Script::Common | Script::Inherited | Script::Unknown => (),
+                script => *script_counts.entry(script).or_default() += 1,
+            }
+        }
+        let mut scripts: Vec<_> = script_counts.into_iter().collect();
+        scripts.sort_by_key(|(script, count)| (usize::MAX - count, script.full_name()));
+
+        Ok(Self {
+            file_name: file_name.to_string(),
+            family,
+            scripts: scripts.into_iter().map(|(script, _)| script).collect(),
+            chars,
+            style,
+            weight: face.weight().to_number(),
+            license,
+            copyright,
+            license_text,
+            subset: false,
+        })
+    }
+
+    /// Returns `true` if the entry of the font selection names the family or file of this font
+    /// or the script it has the most glyphs for, like `Egyptian_Hieroglyphs`.
+    pub fn matches(&self, entry: &str) -> bool {
+        entry == self.family ||
+            entry == self.file_name ||
+            Script::from_full_name(entry)
+                .is_some_and(|script| self.scripts.first() == Some(&script))
+    }
+
+    /// Writes the metadata as a `FontInfo` expression, `license_text` indexes `LICENSE_TEXTS`.
+    pub fn make_font_info(
+        &self,
+        group: &str,
+        index: usize,
+        license_text: usize,
+    ) -> anyhow::Result<String> {
+        let mut out = String::new();
+        write!(out, "\n    FontInfo {{")?;
+        write!(out, "\n        family: {:?},", self.family)?;
+        write!(out, "\n        file_name: {:?},", self.file_name)?;
+        write!(out, "\n        group: FontGroup::{group},")?;
+        let scripts: Vec<_> = self
+            .scripts
+            .iter()
+            .map(|script| format!("Script::{script:?}"))
+            .collect();
+        write!(out, "\n        scripts: &[{}],", scripts.join(", "))?;
+        let coverage: Vec<_> = char_ranges(&self.chars)
+            .into_iter()
+            .map(|range| format!("{:?}..={:?}", range.start(), range.end()))
+            .collect();
+        write!(out, "\n        coverage: &[{}],", coverage.join(", "))?;
+        write!(out, "\n        style: FontStyle::{},", self.style)?;
+        write!(out, "\n        weight: {},", self.weight)?;
+        write!(out, "\n        license: {:?},", self.license)?;
+        write!(out, "\n        copyright: {:?},", self.copyright)?;
+        write!(
+            out,
+            "\n        license_text: LICENSE_TEXTS[{license_text}],"
+        )?;
+        write!(out, "\n        subset: {},", self.subset)?;
+        write!(out, "\n        index: {index},")?;
+        write!(out, "\n    }},")?;
+        Ok(out)
+    }
+}
+
+/// Returns the English name with the given id.
+fn name(face: &Face, name_id: u16) -> Option<String> {
+    face.names()
+        .into_iter()
+        .filter(|name| name.name_id == name_id && name.is_unicode())
+        .find(|name| name.language() == Language::English_UnitedStates)
+        .and_then(|name| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use hieroglyph::fonts;
+
+    use super::*;
+
+    #[test]
+    fn entries_match_family_file_name_and_script() {
+        let info = &fonts::all()[0];
+        let font = FontMeta::read(info.data(), info.file_name).unwrap();
+        assert_eq!(font.family, info.family);
+        assert!(font.matches(info.family));
+        assert!(font.matches(info.file_name));
+        assert!(!font.matches("Not A Font"));
+
+        // only the script with the most glyphs matches
+        let [first, rest @ ..] = info.scripts else {
+            panic!("expected {} to cover a script", info.file_name);
+        };
+        assert!(font.matches(first.full_name()));
+        for script in rest {
+            assert!(!font.matches(script.full_name()));
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Build-time font selection, see [`font_selection`].
+
+use std::{env, fs};
+
+/// Reads the fonts to embed from `HIEROGLYPH_FONTS` and the file named by
+/// `HIEROGLYPH_FONT_MANIFEST`, [`None`] if neither is set.
+///
+/// `HIEROGLYPH_FONTS` separates the entries by commas, the manifest has one entry per line and
+/// ignores everything after a `#`.
+/// A selection without any entries, like `HIEROGLYPH_FONTS=""`, is treated as unset.
+pub fn font_selection() -> anyhow::Result<Option<Vec<String>>> {
+    println!("cargo:rerun-if-env-changed=HIEROGLYPH_FONTS");
+    println!("cargo:rerun-if-env-changed=HIEROGLYPH_FONT_MANIFEST");
+
+    let list = env::var("HIEROGLYPH_FONTS").ok();
+    let manifest = match env::var("HIEROGLYPH_FONT_MANIFEST") {
+        Ok(path) => {
+            println!("cargo:rerun-if-changed={path}");
+            let manifest = fs::read_to_string(&path)
+                .map_err(|e| anyhow::anyhow!("could not read font manifest {path}: {e}"))?;
+            Some(manifest)
+        }
+        Err(_) => None,
+    };
+    Ok(selection(list.as_deref(), manifest.as_deref()))
+}
+
+/// Collects the entries of the `HIEROGLYPH_FONTS` list and the manifest, [`None`] if there are
+/// none.
+fn selection(list: Option<&str>, manifest: Option<&str>) -> Option<Vec<String>> {
+    let list = list
+        .into_iter()
+        .flat_map(|list| list.split(','))
+        .map(str::trim);
+    let lines = manifest
+        .into_iter()
+        .flat_map(str::lines)
+        .map(|line| line.split('#').next().unwrap_or_default().trim());
+    let entries: Vec<String> = list
+        .chain(lines)
+        .filter(|entry| !entry.is_empty())
+        .map(String::from)
+        .collect();
+    (!entries.is_empty()).then_some(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selection_is_parsed() {
+        let manifest =
+            "# fonts for the kiosk\nNoto Serif  # body text\n\n  NotoSansMath-Regular.otf\n";
+        assert_eq!(
+            selection(Some(" Noto Sans, ,Egyptian_Hieroglyphs,"), Some(manifest)),
+            Some(vec![
+                "Noto Sans".to_string(),
+                "Egyptian_Hieroglyphs".to_string(),
+                "Noto Serif".to_string(),
+                "NotoSansMath-Regular.otf".to_string(),
+            ])
+        );
+        assert_eq!(selection(None, None), None);
+        assert_eq!(selection(Some(""), None), None);
+        assert_eq!(selection(Some(" , "), Some("# only a comment\n")), None);
+    }
+}
@@ -0,0 +1,1085 @@
+//! Build-time font subsetting, see [`subset_font`].
+
+use std::collections::HashSet;
+use std::ops::{Range, RangeInclusive};
+use std::{env, fs};
+
+use ttf_parser::gsub::{SingleSubstitution, SubstitutionSubtable};
+use ttf_parser::opentype_layout::Coverage;
+use ttf_parser::{Face, GlyphId, OutlineBuilder, Tag};
+
+/// CFF charstring of an empty glyph, a single `endchar`.
+const EMPTY_CHARSTRING: &[u8] = &[14];
+
+/// Reads the characters to keep from `HIEROGLYPH_SUBSET` and the file named by
+/// `HIEROGLYPH_SUBSET_TEXT`, [`None`] if neither is set.
+///
+/// `HIEROGLYPH_SUBSET` is a comma-separated list of code points and ranges like
+/// `U+0020-007E, U+13000-1342F`, the text file keeps every character it contains.
+pub fn subset_selection() -> anyhow::Result<Option<Vec<RangeInclusive<char>>>> {
+    println!("cargo:rerun-if-env-changed=HIEROGLYPH_SUBSET");
+    println!("cargo:rerun-if-env-changed=HIEROGLYPH_SUBSET_TEXT");
+
+    let mut chars: Option<Vec<RangeInclusive<char>>> = None;
+    if let Ok(list) = env::var("HIEROGLYPH_SUBSET") {
+        for entry in list
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let range = parse_range(entry)
+                .ok_or_else(|| anyhow::anyhow!("invalid range {entry:?} in HIEROGLYPH_SUBSET"))?;
+            chars.get_or_insert_default().push(range);
+        }
+    }
+    if let Ok(path) = env::var("HIEROGLYPH_SUBSET_TEXT") {
+        println!("cargo:rerun-if-changed={path}");
+        let text = fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("could not read subset text {path}: {e}"))?;
+        chars
+            .get_or_insert_default()
+            .extend(text.chars().map(|c| c..=c));
+    }
+    Ok(chars)
+}
+
+/// Parses `U+0041`, `U+0041-005A` or the same without the `U+`.
+fn parse_range(entry: &str) -> Option<RangeInclusive<char>> {
+    let code_point = |s: &str| {
+        let s = s.trim();
+        let s = s
+            .strip_prefix("U+")
+            .or_else(|| s.strip_prefix("u+"))
+            .unwrap_or(s);
+        char::from_u32(u32::from_str_radix(s, 16).ok()?)
+    };
+    match entry.split_once('-') {
+        Some((start, end)) => Some(code_point(start)?..=code_point(end)?),
+        None => code_point(entry).map(|c| c..=c),
+    }
+}
+
+/// Removes the outlines of glyphs only used by characters outside of `keep` and drops those
+/// characters from the `cmap`, so other fonts are used for them.
+///
+/// Glyph ids stay the same, so the layout and metrics tables remain valid without rewriting them.
+/// Glyphs without a character, like ligatures and contextual forms, are kept if a `GSUB`
+/// substitution can produce them from kept glyphs.
+/// Variation sequences of kept characters are kept in a format 14 subtable, together with their
+/// glyphs.
+/// Returns [`None`] if the font cannot be subset, e.g. because it uses CFF2 or CID-keyed outlines.
+pub fn subset_font(data: &[u8], keep: &[RangeInclusive<char>]) -> Option<Vec<u8>> {
+    let face = Face::parse(data, 0).ok()?;
+    let keeps = |c: char| keep.iter().any(|range| range.contains(&c));
+
+    let mut mapping = Vec::new();
+    for subtable in face
+        .tables()
+        .cmap
+        .into_iter()
+        .flat_map(|cmap| cmap.subtables)
+    {
+        if subtable.is_unicode() {
+            subtable.codepoints(|codepoint| {
+                if let Some(glyph) = subtable.glyph_index(codepoint) &&
+                    glyph.0 != 0
+                {
+                    mapping.push((codepoint, glyph.0));
+                }
+            });
+        }
+    }
+    mapping.sort_unstable();
+    mapping.dedup_by_key(|(codepoint, _)| *codepoint);
+
+    let mut keep_glyphs = vec![false; face.number_of_glyphs() as usize];
+    keep_glyphs[0] = true;
+    let mapping: Vec<_> = mapping
+        .into_iter()
+        .filter(|(codepoint, _)| char::from_u32(*codepoint).is_some_and(keeps))
+        .collect();
+
+    let mut tables = raw_tables(data)?;
+    let table = |tables: &[(Tag, Vec<u8>)], tag: &[u8; 4]| {
+        tables
+            .iter()
+            .position(|(table_tag, _)| *table_tag == Tag::from_bytes(tag))
+    };
+    let cmap = table(&tables, b"cmap")?;
+    let kept: HashSet<u32> = mapping.iter().map(|&(codepoint, _)| codepoint).collect();
+    let mut selectors = variation_selectors(&tables[cmap].1)?;
+    for selector in &mut selectors {
+        selector
+            .default
+            .retain(|codepoint| kept.contains(codepoint));
+        selector
+            .non_default
+            .retain(|(codepoint, _)| kept.contains(codepoint));
+    }
+    selectors.retain(|selector| !selector.default.is_empty() || !selector.non_default.is_empty());
+
+    let mapped = mapping.iter().map(|&(_, glyph)| glyph);
+    let variants = selectors
+        .iter()
+        .flat_map(|selector| &selector.non_default)
+        .map(|&(_, glyph)| glyph);
+    for glyph in mapped.chain(variants) {
+        *keep_glyphs.get_mut(glyph as usize)? = true;
+    }
+    keep_substitutes(&face, &mut keep_glyphs);
+    if let Some(cff) = table(&tables, b"CFF ") {
+        tables[cff].1 = subset_cff(&tables[cff].1, &keep_glyphs)?;
+    } else {
+        let (glyf, loca, head) = (
+            table(&tables, b"glyf")?,
+            table(&tables, b"loca")?,
+            table(&tables, b"head")?,
+        );
+        let long_loca = *tables[head].1.get(51)? == 1;
+        let (new_glyf, new_loca) = subset_glyf(
+            &tables[glyf].1,
+            &tables[loca].1,
+            long_loca,
+            &mut keep_glyphs,
+        )?;
+        tables[glyf].1 = new_glyf;
+        tables[loca].1 = new_loca;
+        tables[head].1[50..52].copy_from_slice(&1u16.to_be_bytes());
+    }
+    tables[cmap].1 = build_cmap(&mapping, &selectors);
+
+    let subset = write_font(data, tables)?;
+    let subset_face = Face::parse(&subset, 0).ok()?;
+    same_outlines(&face, &subset_face, &mapping, &selectors).then_some(subset)
+}
+
+/// Marks the glyphs `GSUB` lookups can substitute kept glyphs with as kept, until no lookup adds
+/// any more.
+///
+/// Contextual lookups only apply other lookups, which are handled on their own, and the context
+/// of a substitution is ignored, so a few unreachable glyphs may remain.
+fn keep_substitutes(face: &Face, keep: &mut [bool]) {
+    let Some(gsub) = face.tables().gsub else {
+        return;
+    };
+    loop {
+        let kept = |glyph: u16| keep.get(glyph as usize).copied().unwrap_or(false);
+        let mut glyphs = Vec::new();
+        for lookup in gsub.lookups {
+            for subtable in lookup.subtables.into_iter::<SubstitutionSubtable>() {
+                match subtable {
+                    SubstitutionSubtable::Single(SingleSubstitution::Format1 {
+                        coverage,
+                        delta,
+                    }) => glyphs.extend(
+                        coverage_glyphs(coverage)
+                            .into_iter()
+                            .filter(|&glyph| kept(glyph))
+                            .map(|glyph| glyph.wrapping_add(delta as u16)),
+                    ),
+                    SubstitutionSubtable::Single(SingleSubstitution::Format2 {
+                        coverage,
+                        substitutes,
+                    }) => glyphs.extend(
+                        coverage_glyphs(coverage)
+                            .into_iter()
+                            .zip(substitutes)
+                            .filter(|&(glyph, _)| kept(glyph))
+                            .map(|(_, substitute)| substitute.0),
+                    ),
+                    SubstitutionSubtable::Multiple(subtable) => glyphs.extend(
+                        coverage_glyphs(subtable.coverage)
+                            .into_iter()
+                            .zip(subtable.sequences)
+                            .filter(|&(glyph, _)| kept(glyph))
+                            .flat_map(|(_, sequence)| sequence.substitutes)
+                            .map(|glyph| glyph.0),
+                    ),
+                    SubstitutionSubtable::Alternate(subtable) => glyphs.extend(
+                        coverage_glyphs(subtable.coverage)
+                            .into_iter()
+                            .zip(subtable.alternate_sets)
+                            .filter(|&(glyph, _)| kept(glyph))
+                            .flat_map(|(_, set)| set.alternates)
+                            .map(|glyph| glyph.0),
+                    ),
+                    SubstitutionSubtable::Ligature(subtable) => glyphs.extend(
+                        coverage_glyphs(subtable.coverage)
+                            .into_iter()
+                            .zip(subtable.ligature_sets)
+                            .filter(|&(glyph, _)| kept(glyph))
+                            .flat_map(|(_, set)| set)
+                            .filter(|ligature| {
+                                ligature.components.into_iter().all(|glyph| kept(glyph.0))
+                            })
+                            .map(|ligature| ligature.glyph.0),
+                    ),
+                    SubstitutionSubtable::ReverseChainSingle(subtable) => glyphs.extend(
+                        coverage_glyphs(subtable.coverage)
+                            .into_iter()
+                            .zip(subtable.substitutes)
+                            .filter(|&(glyph, _)| kept(glyph))
+                            .map(|(_, substitute)| substitute.0),
+                    ),
+                    SubstitutionSubtable::Context(_) | SubstitutionSubtable::ChainContext(_) => (),
+                }
+            }
+        }
+
+        let mut changed = false;
+        for glyph in glyphs {
+            if let Some(keep) = keep.get_mut(glyph as usize) &&
+                !*keep
+            {
+                *keep = true;
+                changed = true;
+            }
+        }
+        if !changed {
+            return;
+        }
+    }
+}
+
+fn coverage_glyphs(coverage: Coverage) -> Vec<u16> {
+    match coverage {
+        Coverage::Format1 { glyphs } => glyphs.into_iter().map(|glyph| glyph.0).collect(),
+        Coverage::Format2 { records } => records
+            .into_iter()
+            .flat_map(|record| record.start.0..=record.end.0)
+            .collect(),
+    }
+}
+
+/// Replaces the charstrings of dropped glyphs in a name-keyed CFF table with empty ones.
+///
+/// Everything after the charstrings moves, so the offsets in the top DICT are rewritten, always
+/// as 5 byte integers to keep the size of the DICT independent of their values.
+fn subset_cff(cff: &[u8], keep: &[bool]) -> Option<Vec<u8>> {
+    const CHARSET: u16 = 15;
+    const ENCODING: u16 = 16;
+    const CHAR_STRINGS: u16 = 17;
+    const PRIVATE: u16 = 18;
+    const SUBRS: u16 = 19;
+    const ROS: u16 = 0x0C1E;
+
+    let header_size = *cff.get(2)? as usize;
+    let (_, top_start) = index(cff, header_size)?;
+    let (top_dicts, top_end) = index(cff, top_start)?;
+    let (_, strings_end) = index(cff, top_end)?;
+    let (_, global_subrs_end) = index(cff, strings_end)?;
+    let [top_dict] = top_dicts.as_slice() else {
+        return None;
+    };
+    let top_dict = dict(cff.get(top_dict.clone())?)?;
+    if top_dict.iter().any(|(op, _)| *op == ROS) {
+        return None;
+    }
+
+    let value = |op| {
+        let (_, operands) = top_dict.iter().find(|(entry_op, _)| *entry_op == op)?;
+        operands.last()?.1
+    };
+    let char_strings_start = usize::try_from(value(CHAR_STRINGS)?).ok()?;
+    if char_strings_start < global_subrs_end {
+        return None;
+    }
+    let (char_strings, char_strings_end) = index(cff, char_strings_start)?;
+    if char_strings.len() != keep.len() {
+        return None;
+    }
+
+    // the local subroutines are addressed relative to the private DICT, so both have to stay on
+    // the same side of the charstrings
+    if let Some((_, operands)) = top_dict.iter().find(|(op, _)| *op == PRIVATE) {
+        let [(_, Some(size)), (_, Some(offset))] = operands.as_slice() else {
+            return None;
+        };
+        let private = usize::try_from(*offset).ok()?..usize::try_from(offset + size).ok()?;
+        let subrs = dict(cff.get(private.clone())?)?
+            .into_iter()
+            .find(|(op, _)| *op == SUBRS)
+            .and_then(|(_, operands)| operands.last()?.1);
+        let is_after = |offset: usize| offset >= char_strings_end;
+        if private.start < char_strings_end && private.end > char_strings_start {
+            return None;
+        }
+        if let Some(subrs) = subrs &&
+            is_after(private.start) != is_after(private.start + usize::try_from(subrs).ok()?)
+        {
+            return None;
+        }
+    }
+
+    let new_char_strings = build_index(
+        char_strings
+            .iter()
+            .zip(keep)
+            .map(|(range, keep)| match keep {
+                true => cff.get(range.clone()),
+                false => Some(EMPTY_CHARSTRING),
+            })
+            .collect::<Option<Vec<_>>>()?,
+    );
+    let char_strings_delta =
+        new_char_strings.len() as i64 - (char_strings_end - char_strings_start) as i64;
+
+    let write_top_dict = |top_delta: i64| {
+        let moved = |offset: i32| {
+            let offset = offset as i64;
+            let delta = match offset as usize >= char_strings_end {
+                true => top_delta + char_strings_delta,
+                false => top_delta,
+            };
+            offset + delta
+        };
+        let mut out = Vec::new();
+        for (op, operands) in &top_dict {
+            for (i, (raw, value)) in operands.iter().enumerate() {
+                let is_offset = match *op {
+                    CHARSET => value.is_some_and(|value| value > 2),
+                    ENCODING => value.is_some_and(|value| value > 1),
+                    CHAR_STRINGS => true,
+                    PRIVATE => i == 1,
+                    _ => false,
+                };
+                match (is_offset, value) {
+                    (true, Some(value)) => {
+                        out.push(29);
+                        out.extend(i32::try_from(moved(*value)).ok()?.to_be_bytes());
+                    }
+                    _ => out.extend(raw),
+                }
+            }
+            if *op > 0xFF {
+                out.push(12);
+            }
+            out.push(*op as u8);
+        }
+        Some(build_index(vec![out.as_slice()]))
+    };
+    let top_delta = write_top_dict(0)?.len() as i64 - (top_end - top_start) as i64;
+
+    let mut out = cff.get(..top_start)?.to_vec();
+    out.extend(write_top_dict(top_delta)?);
+    out.extend(cff.get(top_end..char_strings_start)?);
+    out.extend(new_char_strings);
+    out.extend(cff.get(char_strings_end..)?);
+    Some(out)
+}
+
+/// Parses a CFF INDEX, returning the ranges of its items and the end of the INDEX.
+fn index(data: &[u8], start: usize) -> Option<(Vec<Range<usize>>, usize)> {
+    let count = u16::from_be_bytes(data.get(start..start + 2)?.try_into().ok()?) as usize;
+    if count == 0 {
+        return Some((Vec::new(), start + 2));
+    }
+    let offset_size = *data.get(start + 2)? as usize;
+    let offsets = (0..=count)
+        .map(|i| {
+            let at = start + 3 + i * offset_size;
+            let bytes = data.get(at..at + offset_size)?;
+            Some(
+                bytes
+                    .iter()
+                    .fold(0, |offset, &byte| offset << 8 | byte as usize),
+            )
+        })
+        .collect::<Option<Vec<_>>>()?;
+    // offsets are relative to the byte before the data
+    let data_start = start + 3 + (count + 1) * offset_size - 1;
+    let items = offsets
+        .windows(2)
+        .map(|pair| data_start + pair[0]..data_start + pair[1])
+        .collect();
+    Some((items, data_start + offsets[count]))
+}
+
+fn build_index(items: Vec<&[u8]>) -> Vec<u8> {
+    let mut out = (items.len() as u16).to_be_bytes().to_vec();
+    if items.is_empty() {
+        return out;
+    }
+    let last_offset = items.iter().map(|item| item.len()).sum::<usize>() + 1;
+    let offset_size = match last_offset {
+        0..=0xFF => 1,
+        0x100..=0xFFFF => 2,
+        0x1_0000..=0xFF_FFFF => 3,
+        _ => 4,
+    };
+    out.push(offset_size as u8);
+    let mut offset = 1;
+    for item in items.iter().map(|item| item.len()).chain([0]) {
+        out.extend(&(offset as u32).to_be_bytes()[4 - offset_size..]);
+        offset += item;
+    }
+    items.into_iter().for_each(|item| out.extend(item));
+    out
+}
+
+/// A DICT operator with its operands as raw bytes and their value if they are integers.
+type DictEntry = (u16, Vec<(Vec<u8>, Option<i32>)>);
+
+fn dict(data: &[u8]) -> Option<Vec<DictEntry>> {
+    let mut entries = Vec::new();
+    let mut operands = Vec::new();
+    let mut i = 0;
+    while let Some(&b0) = data.get(i) {
+        let (len, value) = match b0 {
+            0..=21 => {
+                let op = match b0 {
+                    12 => 0x0C00 | *data.get(i + 1)? as u16,
+                    _ => b0 as u16,
+                };
+                i += if b0 == 12 { 2 } else { 1 };
+                entries.push((op, std::mem::take(&mut operands)));
+                continue;
+            }
+            28 => (
+                3,
+                i16::from_be_bytes(data.get(i + 1..i + 3)?.try_into().ok()?) as i32,
+            ),
+            29 => (
+                5,
+                i32::from_be_bytes(data.get(i + 1..i + 5)?.try_into().ok()?),
+            ),
+            30 => {
+                let nibbles = data.get(i + 1..)?;
+                let len = nibbles
+                    .iter()
+                    .position(|byte| byte >> 4 == 0xF || byte & 0xF == 0xF)?;
+                operands.push((data[i..i + len + 2].to_vec(), None));
+                i += len + 2;
+                continue;
+            }
+            32..=246 => (1, b0 as i32 - 139),
+            247..=250 => (2, (b0 as i32 - 247) * 256 + *data.get(i + 1)? as i32 + 108),
+            251..=254 => (2, -(b0 as i32 - 251) * 256 - *data.get(i + 1)? as i32 - 108),
+            _ => return None,
+        };
+        operands.push((data.get(i..i + len)?.to_vec(), Some(value)));
+        i += len;
+    }
+    Some(entries)
+}
+
+/// Empties dropped glyphs in `glyf`, keeping the components of every kept composite glyph.
+///
+/// The returned `loca` always uses the long format.
+fn subset_glyf(
+    glyf: &[u8],
+    loca: &[u8],
+    long_loca: bool,
+    keep: &mut [bool],
+) -> Option<(Vec<u8>, Vec<u8>)> {
+    let offsets = (0..=keep.len())
+        .map(|i| match long_loca {
+            true => Some(u32::from_be_bytes(loca.get(i * 4..i * 4 + 4)?.try_into().ok()?) as usize),
+            false => {
+                Some(u16::from_be_bytes(loca.get(i * 2..i * 2 + 2)?.try_into().ok()?) as usize * 2)
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let glyph = |id: usize| glyf.get(*offsets.get(id)?..*offsets.get(id + 1)?);
+
+    let mut queue: Vec<usize> = (0..keep.len()).filter(|&id| keep[id]).collect();
+    while let Some(id) = queue.pop() {
+        for component in components(glyph(id)?)? {
+            if let Some(keep) = keep.get_mut(component) &&
+                !*keep
+            {
+                *keep = true;
+                queue.push(component);
+            }
+        }
+    }
+
+    let mut new_glyf = Vec::new();
+    let mut new_loca = Vec::new();
+    for (id, keep) in keep.iter().enumerate() {
+        new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+        if *keep {
+            new_glyf.extend(glyph(id)?);
+            new_glyf.resize(new_glyf.len().next_multiple_of(4), 0);
+        }
+    }
+    new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+    Some((new_glyf, new_loca))
+}
+
+/// Returns the glyph ids a composite glyph is built from, nothing for simple glyphs.
+fn components(glyph: &[u8]) -> Option<Vec<usize>> {
+    const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+    const WE_HAVE_A_SCALE: u16 = 0x0008;
+    const MORE_COMPONENTS: u16 = 0x0020;
+    const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+    const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+    let read = |at: usize| Some(u16::from_be_bytes(glyph.get(at..at + 2)?.try_into().ok()?));
+    let mut components = Vec::new();
+    if glyph.is_empty() || (read(0)? as i16) >= 0 {
+        return Some(components);
+    }
+
+    let mut at = 10;
+    loop {
+        let flags = read(at)?;
+        components.push(read(at + 2)? as usize);
+        at += 4;
+        at += if flags & ARG_1_AND_2_ARE_WORDS != 0 { 4 } else { 2 };
+        at += match flags {
+            _ if flags & WE_HAVE_A_SCALE != 0 => 2,
+            _ if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 => 4,
+            _ if flags & WE_HAVE_A_TWO_BY_TWO != 0 => 8,
+            _ => 0,
+        };
+        if flags & MORE_COMPONENTS == 0 {
+            return Some(components);
+        }
+    }
+}
+
+/// A variation selector of a format 14 `cmap` subtable.
+///
+/// `default` lists the characters drawn with their usual glyph in sequence with the selector,
+/// `non_default` the characters drawn with another glyph.
+#[derive(Debug, Clone, PartialEq)]
+struct VariationSelector {
+    selector: u32,
+    default: Vec<u32>,
+    non_default: Vec<(u32, u16)>,
+}
+
+/// Reads the variation selectors of the format 14 subtable of a `cmap` table, none if it has no
+/// such subtable.
+fn variation_selectors(cmap: &[u8]) -> Option<Vec<VariationSelector>> {
+    let read_u16 = |at: usize| Some(u16::from_be_bytes(cmap.get(at..at + 2)?.try_into().ok()?));
+    let read_u24 = |at: usize| {
+        let bytes = cmap.get(at..at + 3)?;
+        Some(u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]))
+    };
+    let read_u32 = |at: usize| Some(u32::from_be_bytes(cmap.get(at..at + 4)?.try_into().ok()?));
+
+    let mut start = None;
+    for i in 0..read_u16(2)? as usize {
+        let record = 4 + i * 8;
+        let offset = read_u32(record + 4)? as usize;
+        if (read_u16(record)?, read_u16(record + 2)?, read_u16(offset)?) == (0, 5, 14) {
+            start = Some(offset);
+        }
+    }
+    let Some(start) = start else {
+        return Some(Vec::new());
+    };
+
+    (0..read_u32(start + 6)? as usize)
+        .map(|i| {
+            let record = start + 10 + i * 11;
+            let mut selector = VariationSelector {
+                selector: read_u24(record)?,
+                default: Vec::new(),
+                non_default: Vec::new(),
+            };
+            // offsets of zero mark missing tables
+            let default = read_u32(record + 3)? as usize;
+            if default != 0 {
+                for range in 0..read_u32(start + default)? as usize {
+                    let at = start + default + 4 + range * 4;
+                    let first = read_u24(at)?;
+                    selector
+                        .default
+                        .extend(first..=first + *cmap.get(at + 3)? as u32);
+                }
+            }
+            let non_default = read_u32(record + 7)? as usize;
+            if non_default != 0 {
+                for mapping in 0..read_u32(start + non_default)? as usize {
+                    let at = start + non_default + 4 + mapping * 5;
+                    selector
+                        .non_default
+                        .push((read_u24(at)?, read_u16(at + 3)?));
+                }
+            }
+            Some(selector)
+        })
+        .collect()
+}
+
+/// Builds a `cmap` with a format 12 subtable for the given sorted mapping and a format 14
+/// subtable for the variation selectors, if there are any.
+fn build_cmap(mapping: &[(u32, u16)], selectors: &[VariationSelector]) -> Vec<u8> {
+    let mut groups: Vec<(u32, u32, u32)> = Vec::new();
+    for &(codepoint, glyph) in mapping {
+        match groups.last_mut() {
+            Some((start, end, start_glyph))
+                if *end + 1 == codepoint && *start_glyph + (codepoint - *start) == glyph as u32 =>
+            {
+                *end = codepoint
+            }
+            _ => groups.push((codepoint, codepoint, glyph as u32)),
+        }
+    }
+
+    let mut segmented = Vec::new();
+    segmented.extend(12u16.to_be_bytes()); // format
+    segmented.extend(0u16.to_be_bytes());
+    segmented.extend((16 + groups.len() as u32 * 12).to_be_bytes());
+    segmented.extend(0u32.to_be_bytes()); // language
+    segmented.extend((groups.len() as u32).to_be_bytes());
+    for (start, end, start_glyph) in groups {
+        segmented.extend(start.to_be_bytes());
+        segmented.extend(end.to_be_bytes());
+        segmented.extend(start_glyph.to_be_bytes());
+    }
+
+    // records are sorted by platform and encoding
+    let mut subtables = Vec::new();
+    if !selectors.is_empty() {
+        subtables.push((0u16, 5u16, build_variation_subtable(selectors))); // unicode variations
+    }
+    subtables.push((3, 10, segmented)); // windows, unicode full repertoire
+
+    let mut out = Vec::new();
+    out.extend(0u16.to_be_bytes()); // version
+    out.extend((subtables.len() as u16).to_be_bytes());
+    let mut offset = 4 + subtables.len() * 8;
+    for (platform, encoding, subtable) in &subtables {
+        out.extend(platform.to_be_bytes());
+        out.extend(encoding.to_be_bytes());
+        out.extend((offset as u32).to_be_bytes());
+        offset += subtable.len();
+    }
+    subtables
+        .into_iter()
+        .for_each(|(_, _, subtable)| out.extend(subtable));
+    out
+}
+
+/// Builds a format 14 `cmap` subtable, the characters of every selector have to be sorted.
+fn build_variation_subtable(selectors: &[VariationSelector]) -> Vec<u8> {
+    let u24 = |value: u32| <[u8; 3]>::try_from(&value.to_be_bytes()[1..]).unwrap();
+    let tables_start = 10 + selectors.len() * 11;
+    let mut records = Vec::new();
+    let mut tables = Vec::new();
+    for selector in selectors {
+        records.extend(u24(selector.selector));
+
+        let mut ranges: Vec<(u32, u8)> = Vec::new();
+        for &codepoint in &selector.default {
+            match ranges.last_mut() {
+                Some((first, additional))
+                    if *first + *additional as u32 + 1 == codepoint && *additional < u8::MAX =>
+                {
+                    *additional += 1
+                }
+                _ => ranges.push((codepoint, 0)),
+            }
+        }
+        match ranges.is_empty() {
+            true => records.extend(0u32.to_be_bytes()),
+            false => {
+                records.extend(((tables_start + tables.len()) as u32).to_be_bytes());
+                tables.extend((ranges.len() as u32).to_be_bytes());
+                for (first, additional) in ranges {
+                    tables.extend(u24(first));
+                    tables.push(additional);
+                }
+            }
+        }
+
+        match selector.non_default.is_empty() {
+            true => records.extend(0u32.to_be_bytes()),
+            false => {
+                records.extend(((tables_start + tables.len()) as u32).to_be_bytes());
+                tables.extend((selector.non_default.len() as u32).to_be_bytes());
+                for &(codepoint, glyph) in &selector.non_default {
+                    tables.extend(u24(codepoint));
+                    tables.extend(glyph.to_be_bytes());
+                }
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    out.extend(14u16.to_be_bytes()); // format
+    out.extend(((tables_start + tables.len()) as u32).to_be_bytes());
+    out.extend((selectors.len() as u32).to_be_bytes());
+    out.extend(records);
+    out.extend(tables);
+    out
+}
+
+/// Returns the tables of a font in the order of its table directory.
+fn raw_tables(data: &[u8]) -> Option<Vec<(Tag, Vec<u8>)>> {
+    let read_u32 = |at: usize| Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?));
+    let count = u16::from_be_bytes(data.get(4..6)?.try_into().ok()?) as usize;
+    (0..count)
+        .map(|i| {
+            let record = 12 + i * 16;
+            let tag = Tag(read_u32(record)?);
+            let offset = read_u32(record + 8)? as usize;
+            let len = read_u32(record + 12)? as usize;
+            Some((tag, data.get(offset..offset + len)?.to_vec()))
+        })
+        .collect()
+}
+
+/// Writes a font with the header of `original` and the given tables, updating all checksums.
+fn write_font(original: &[u8], tables: Vec<(Tag, Vec<u8>)>) -> Option<Vec<u8>> {
+    fn checksum(data: &[u8]) -> u32 {
+        data.chunks(4).fold(0u32, |sum, chunk| {
+            let mut word = [0; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            sum.wrapping_add(u32::from_be_bytes(word))
+        })
+    }
+
+    let mut out = original.get(..12)?.to_vec();
+    let mut offset = 12 + tables.len() * 16;
+    let mut head_offset = None;
+    for (tag, table) in &tables {
+        if tag.to_bytes() == *b"head" {
+            head_offset = Some(offset);
+        }
+        let mut table = table.clone();
+        if tag.to_bytes() == *b"head" {
+            table.get_mut(8..12)?.fill(0);
+        }
+        out.extend(tag.0.to_be_bytes());
+        out.extend(checksum(&table).to_be_bytes());
+        out.extend((offset as u32).to_be_bytes());
+        out.extend((table.len() as u32).to_be_bytes());
+        offset += table.len().next_multiple_of(4);
+    }
+    for (tag, table) in tables {
+        let start = out.len();
+        out.extend(table);
+        if tag.to_bytes() == *b"head" {
+            out.get_mut(start + 8..start + 12)?.fill(0);
+        }
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+
+    if let Some(head) = head_offset {
+        let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&out));
+        out.get_mut(head + 8..head + 12)?
+            .copy_from_slice(&adjustment.to_be_bytes());
+    }
+    Some(out)
+}
+
+/// Checks that every kept character and variation sequence still has the same outline as in the
+/// original font.
+fn same_outlines(
+    original: &Face,
+    subset: &Face,
+    mapping: &[(u32, u16)],
+    selectors: &[VariationSelector],
+) -> bool {
+    #[derive(Default, PartialEq)]
+    struct Recorder(Vec<f32>);
+
+    impl OutlineBuilder for Recorder {
+        fn move_to(&mut self, x: f32, y: f32) {
+            self.0.extend([0.0, x, y]);
+        }
+
+        fn line_to(&mut self, x: f32, y: f32) {
+            self.0.extend([1.0, x, y]);
+        }
+
+        fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+            self.0.extend([2.0, x1, y1, x, y]);
+        }
+
+        fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+            self.0.extend([3.0, x1, y1, x2, y2, x, y]);
+        }
+
+        fn close(&mut self) {
+            self.0.push(4.0);
+        }
+    }
+
+    let outline = |face: &Face, glyph| {
+        let mut recorder = Recorder::default();
+        let bbox = face.outline_glyph(glyph, &mut recorder);
+        (bbox, recorder)
+    };
+    let char = |codepoint| char::from_u32(codepoint).unwrap_or_default();
+    let subset_chars: HashSet<u32> = subset
+        .tables()
+        .cmap
+        .into_iter()
+        .flat_map(|cmap| cmap.subtables)
+        .flat_map(|subtable| {
+            let mut codepoints = Vec::new();
+            subtable.codepoints(|codepoint| codepoints.push(codepoint));
+            codepoints
+        })
+        .collect();
+    let same_chars = subset_chars.len() == mapping.len() &&
+        mapping.iter().all(|&(codepoint, glyph)| {
+            subset_chars.contains(&codepoint) &&
+                subset.glyph_index(char(codepoint)) == Some(GlyphId(glyph)) &&
+                outline(original, GlyphId(glyph)) == outline(subset, GlyphId(glyph))
+        });
+    let same_variations = selectors.iter().all(|selector| {
+        let defaults = selector.default.iter().copied();
+        let non_defaults = selector.non_default.iter().map(|&(codepoint, _)| codepoint);
+        defaults.chain(non_defaults).all(|codepoint| {
+            let variation = char(selector.selector);
+            let glyph = subset.glyph_variation_index(char(codepoint), variation);
+            glyph == original.glyph_variation_index(char(codepoint), variation) &&
+                glyph.is_some_and(|glyph| outline(original, glyph) == outline(subset, glyph))
+        })
+    });
+    same_chars && same_variations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VS1: char = '\u{FE00}';
+    const VS2: char = '\u{FE01}';
+
+    const GLYPHS: u16 = 8;
+
+    /// Builds a font with [`GLYPHS`] glyphs from the given outline tables.
+    ///
+    /// `A`, `B` and `C` map to glyphs 1, 2 and 4, `A` followed by `VS1` maps to glyph 3.
+    /// `GSUB` substitutes glyph 1 with 5, glyph 5 with 6 and glyph 2 with 7.
+    fn font(sfnt_version: &[u8; 4], outlines: Vec<(&[u8; 4], Vec<u8>)>) -> Vec<u8> {
+        let mut head = vec![0; 54];
+        head[..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+        head[12..16].copy_from_slice(&0x5F0F_3CF5u32.to_be_bytes());
+        head[18..20].copy_from_slice(&1000u16.to_be_bytes());
+        head[50..52].copy_from_slice(&1u16.to_be_bytes()); // long loca
+        let mut hhea = vec![0; 36];
+        hhea[..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+        hhea[34..36].copy_from_slice(&GLYPHS.to_be_bytes());
+        let mut maxp = 0x0000_5000u32.to_be_bytes().to_vec();
+        maxp.extend(GLYPHS.to_be_bytes());
+        let hmtx = words(&[500, 0].repeat(GLYPHS as usize));
+        let selectors = [
+            VariationSelector {
+                selector: VS1 as u32,
+                default: vec!['C' as u32],
+                non_default: vec![('A' as u32, 3)],
+            },
+            VariationSelector {
+                selector: VS2 as u32,
+                default: vec!['A' as u32, 'B' as u32],
+                non_default: vec![],
+            },
+        ];
+        let cmap = build_cmap(
+            &[('A' as u32, 1), ('B' as u32, 2), ('C' as u32, 4)],
+            &selectors,
+        );
+
+        let mut tables: Vec<_> = [
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"maxp", maxp),
+            (b"hmtx", hmtx),
+            (b"cmap", cmap),
+            // the second substitution of glyph 5 only applies once the first one is kept
+            (b"GSUB", gsub(&[(&[5], &[6]), (&[1, 2], &[5, 7])])),
+        ]
+        .into_iter()
+        .chain(outlines)
+        .map(|(tag, table)| (Tag::from_bytes(tag), table))
+        .collect();
+        tables.sort_by_key(|(tag, _)| *tag);
+        let mut header = sfnt_version.to_vec();
+        header.extend((tables.len() as u16).to_be_bytes());
+        header.extend([0; 6]);
+        write_font(&header, tables).unwrap()
+    }
+
+    fn words(values: &[u16]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_be_bytes())
+            .collect()
+    }
+
+    /// Builds a `GSUB` table with a single substitution lookup per pair of sorted input glyphs
+    /// and their substitutes.
+    fn gsub(lookups: &[(&[u16], &[u16])]) -> Vec<u8> {
+        let lookups: Vec<_> = lookups
+            .iter()
+            .map(|(glyphs, substitutes)| {
+                let count = glyphs.len() as u16;
+                // a lookup of type 1 with one subtable in format 2, followed by its coverage
+                let mut lookup = words(&[1, 0, 1, 8, 2, 6 + 2 * count, count]);
+                lookup.extend(words(substitutes));
+                lookup.extend(words(&[1, count]));
+                lookup.extend(words(glyphs));
+                lookup
+            })
+            .collect();
+
+        // empty script and feature lists, followed by the lookup list
+        let mut out = words(&[1, 0, 10, 12, 14, 0, 0, lookups.len() as u16]);
+        let mut offset = 2 + 2 * lookups.len();
+        for lookup in &lookups {
+            out.extend(words(&[offset as u16]));
+            offset += lookup.len();
+        }
+        lookups.into_iter().for_each(|lookup| out.extend(lookup));
+        out
+    }
+
+    fn simple_glyph(points: &[(i16, i16)]) -> Vec<u8> {
+        let xs: Vec<i16> = points.iter().map(|&(x, _)| x).collect();
+        let ys: Vec<i16> = points.iter().map(|&(_, y)| y).collect();
+        let mut out = 1i16.to_be_bytes().to_vec(); // number of contours
+        for bound in [
+            xs.iter().min(),
+            ys.iter().min(),
+            xs.iter().max(),
+            ys.iter().max(),
+        ] {
+            out.extend(bound.unwrap().to_be_bytes());
+        }
+        out.extend((points.len() as u16 - 1).to_be_bytes()); // end of the contour
+        out.extend(0u16.to_be_bytes()); // no instructions
+        out.extend(vec![1; points.len()]); // on curve, coordinates as words
+        for coordinates in [xs, ys] {
+            let mut previous = 0;
+            for coordinate in coordinates {
+                out.extend((coordinate - previous).to_be_bytes());
+                previous = coordinate;
+            }
+        }
+        out
+    }
+
+    fn composite_glyph(component: u16, bbox: [i16; 4]) -> Vec<u8> {
+        const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+        const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+
+        let mut out = (-1i16).to_be_bytes().to_vec();
+        bbox.iter()
+            .for_each(|bound| out.extend(bound.to_be_bytes()));
+        out.extend((ARG_1_AND_2_ARE_WORDS | ARGS_ARE_XY_VALUES).to_be_bytes());
+        out.extend(component.to_be_bytes());
+        out.extend([0i16, 0].iter().flat_map(|offset| offset.to_be_bytes()));
+        out
+    }
+
+    fn cff(char_strings: Vec<&[u8]>) -> Vec<u8> {
+        let name = build_index(vec![b"Test"]);
+        let strings = build_index(vec![]);
+        let global_subrs = build_index(vec![]);
+        let char_strings = build_index(char_strings);
+        // the offsets are 5 byte integers, so the size of the top DICT doesn't depend on them
+        let top_dict = |char_strings_offset: usize, private_offset: usize| {
+            let mut dict = vec![29];
+            dict.extend((char_strings_offset as i32).to_be_bytes());
+            dict.push(17);
+            dict.push(139); // empty private DICT
+            dict.push(29);
+            dict.extend((private_offset as i32).to_be_bytes());
+            dict.push(18);
+            build_index(vec![&dict])
+        };
+
+        let char_strings_offset =
+            4 + name.len() + top_dict(0, 0).len() + strings.len() + global_subrs.len();
+        let private_offset = char_strings_offset + char_strings.len();
+        let mut out = vec![1, 0, 4, 4];
+        out.extend(name);
+        out.extend(top_dict(char_strings_offset, private_offset));
+        out.extend(strings);
+        out.extend(global_subrs);
+        out.extend(char_strings);
+        out
+    }
+
+    /// Subsets the font to `A` and checks which glyphs keep their outlines.
+    fn assert_subset(font: &[u8], kept: &[u16], dropped: &[u16]) {
+        let original = Face::parse(font, 0).unwrap();
+        let subset = subset_font(font, &['A'..='A']).unwrap();
+        let face = Face::parse(&subset, 0).unwrap();
+
+        assert_eq!(face.glyph_index('A'), Some(GlyphId(1)));
+        assert_eq!(face.glyph_index('B'), None);
+        assert_eq!(face.glyph_index('C'), None);
+        assert_eq!(face.glyph_variation_index('A', VS1), Some(GlyphId(3)));
+        assert_eq!(face.glyph_variation_index('A', VS2), Some(GlyphId(1)));
+        assert_eq!(face.glyph_variation_index('B', VS2), None);
+        assert_eq!(face.glyph_variation_index('C', VS1), None);
+
+        let bbox = |face: &Face, glyph| face.glyph_bounding_box(GlyphId(glyph));
+        for &glyph in kept {
+            assert!(bbox(&original, glyph).is_some());
+            assert_eq!(bbox(&face, glyph), bbox(&original, glyph), "glyph {glyph}");
+        }
+        for &glyph in dropped {
+            assert!(bbox(&original, glyph).is_some());
+            assert_eq!(bbox(&face, glyph), None, "glyph {glyph}");
+        }
+    }
+
+    #[test]
+    fn glyf_fonts_are_subset() {
+        let glyphs = [
+            vec![],
+            simple_glyph(&[(10, 10), (90, 10), (90, 90), (10, 90)]),
+            simple_glyph(&[(20, 20), (80, 20), (50, 80)]),
+            composite_glyph(2, [20, 20, 80, 80]),
+            simple_glyph(&[(30, 30), (70, 30), (70, 70)]),
+            simple_glyph(&[(10, 10), (50, 10), (50, 50)]),
+            simple_glyph(&[(10, 10), (60, 10), (10, 60)]),
+            simple_glyph(&[(20, 20), (60, 20), (60, 60), (20, 60)]),
+        ];
+        let mut glyf = Vec::new();
+        let mut loca = Vec::new();
+        for glyph in &glyphs {
+            loca.extend((glyf.len() as u32).to_be_bytes());
+            glyf.extend(glyph);
+        }
+        loca.extend((glyf.len() as u32).to_be_bytes());
+
+        let font = font(&[0, 1, 0, 0], vec![(b"glyf", glyf), (b"loca", loca)]);
+        // the variant of `A` is built from the glyph of `B`
+        assert_subset(&font, &[1, 2, 3, 5, 6], &[4, 7]);
+    }
+
+    #[test]
+    fn cff_fonts_are_subset() {
+        // numbers from -107 to 107 are stored as a single byte, shifted by 139
+        let (rmoveto, rlineto, endchar) = (21, 5, 14);
+        let char_strings: [&[u8]; GLYPHS as usize] = [
+            &[endchar],
+            &[
+                149, 149, rmoveto, 219, 139, rlineto, 139, 219, rlineto, 59, 139, rlineto, endchar,
+            ],
+            &[
+                159, 159, rmoveto, 199, 139, rlineto, 109, 199, rlineto, endchar,
+            ],
+            &[
+                149, 149, rmoveto, 219, 139, rlineto, 139, 179, rlineto, endchar,
+            ],
+            &[
+                169, 169, rmoveto, 179, 139, rlineto, 139, 179, rlineto, endchar,
+            ],
+            &[
+                149, 149, rmoveto, 179, 139, rlineto, 139, 179, rlineto, endchar,
+            ],
+            &[
+                149, 149, rmoveto, 189, 139, rlineto, 89, 189, rlineto, endchar,
+            ],
+            &[
+                159, 159, rmoveto, 179, 139, rlineto, 139, 179, rlineto, 99, 139, rlineto, endchar,
+            ],
+        ];
+
+        let font = font(b"OTTO", vec![(b"CFF ", cff(char_strings.to_vec()))]);
+        assert_subset(&font, &[1, 3, 5, 6], &[2, 4, 7]);
+    }
+}
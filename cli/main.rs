@@ -0,0 +1,318 @@
+use std::fs;
+use std::io::{self, Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow, bail};
+use clap::{Parser, ValueEnum};
+use hieroglyph::{Canvas, DrawingContext, FontOrder, FontPriority, Layout, Segments};
+use image::{ImageFormat, RgbaImage};
+
+/// Render obscure scripts and emojis to PNG, SVG or the terminal.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Args {
+    /// Text to render.
+    #[arg(required_unless_present = "file")]
+    text: Option<String>,
+
+    /// Read the text from a file instead, use `-` for stdin.
+    #[arg(short, long, conflicts_with = "text")]
+    file: Option<PathBuf>,
+
+    /// Write the output to this file instead of stdout.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Output format, inferred from the output file extension if not set.
+    ///
+    /// Without an output file the text is printed to the terminal using ANSI colors.
+    #[arg(short = 'F', long, value_enum)]
+    format: Option<Format>,
+
+    /// Font size in pixels.
+    #[arg(short = 's', long, default_value_t = 32.0)]
+    font_size: f32,
+
+    /// Text color as `RRGGBB` or `RRGGBBAA` hex code.
+    #[arg(short, long, default_value = "000000", value_parser = parse_color)]
+    color: [u8; 4],
+
+    /// Background color as `RRGGBB` or `RRGGBBAA` hex code, transparent if not set.
+    #[arg(short, long, value_parser = parse_color)]
+    background: Option<[u8; 4]>,
+
+    /// Which font family to prefer.
+    #[arg(long, value_enum, default_value_t = FontOrderArg::Sans)]
+    font_order: FontOrderArg,
+
+    /// Directory with additional fonts, preferred over the bundled ones, may be repeated.
+    #[arg(long, value_name = "DIR")]
+    font_dir: Vec<PathBuf>,
+
+    /// Locale used for shaping.
+    #[arg(short, long, default_value = "en")]
+    locale: String,
+
+    /// Padding around the text in pixels.
+    #[arg(short, long, default_value_t = 0, value_parser = parse_padding)]
+    padding: u32,
+
+    /// Maximum width of the output in pixels, the font size is reduced to fit.
+    #[arg(short = 'w', long)]
+    max_width: Option<u32>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Png,
+    Svg,
+    /// ANSI colored half-block characters.
+    Ansi,
+    /// DEC sixel graphics.
+    Sixel,
+    /// Kitty terminal graphics protocol.
+    Kitty,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum FontOrderArg {
+    Sans,
+    Serif,
+    Mono,
+}
+
+impl From<FontOrderArg> for FontOrder {
+    fn from(value: FontOrderArg) -> Self {
+        match value {
+            FontOrderArg::Sans => FontOrder::SansFirst,
+            FontOrderArg::Serif => FontOrder::SerifFirst,
+            FontOrderArg::Mono => FontOrder::MonoFirst,
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let text = read_text(&args)?;
+    let format = output_format(&args)?;
+    let segments = Segments::new(&text);
+
+    let mut builder = DrawingContext::configure()
+        .font_order(args.font_order.into())
+        .locale(args.locale.clone());
+    for font_dir in &args.font_dir {
+        builder = builder.font_dir(font_dir, FontPriority::BeforeBundled);
+    }
+    let mut ctx = builder.try_build()?;
+    let [r, g, b, a] = args.color;
+    ctx.rgba(r, g, b, a);
+    let layout = fit_font_size(&mut ctx, &segments, &args)?;
+
+    let output = match format {
+        Format::Svg => render_svg(&mut ctx, &layout, &args).into_bytes(),
+        Format::Png => {
+            let canvas = render_canvas(&mut ctx, &layout, &args);
+            let (width, height) = (canvas.width(), canvas.height());
+            let image = RgbaImage::from_raw(width, height, canvas.into_bytes())
+                .expect("canvas size matches its buffer");
+            let mut png = Cursor::new(Vec::new());
+            image.write_to(&mut png, ImageFormat::Png)?;
+            png.into_inner()
+        }
+        Format::Ansi => render_canvas(&mut ctx, &layout, &args)
+            .to_ansi()
+            .into_bytes(),
+        Format::Sixel => render_canvas(&mut ctx, &layout, &args)
+            .to_sixel()
+            .into_bytes(),
+        Format::Kitty => {
+            let mut kitty = render_canvas(&mut ctx, &layout, &args).to_kitty();
+            kitty.push('\n');
+            kitty.into_bytes()
+        }
+    };
+
+    match &args.output {
+        Some(path) => {
+            fs::write(path, output).with_context(|| format!("writing {}", path.display()))
+        }
+        None => Ok(io::stdout().lock().write_all(&output)?),
+    }
+}
+
+fn read_text(args: &Args) -> Result<String> {
+    let text = match (&args.text, &args.file) {
+        (Some(text), _) => text.clone(),
+        (None, Some(path)) if path == Path::new("-") => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text)?;
+            text
+        }
+        (None, Some(path)) => {
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?
+        }
+        (None, None) => unreachable!("clap requires either text or file"),
+    };
+
+    let text = text.trim_end_matches(['\r', '\n']);
+    if text.contains('\n') {
+        bail!("line breaks are not supported, render each line separately");
+    }
+    Ok(text.to_string())
+}
+
+fn output_format(args: &Args) -> Result<Format> {
+    if let Some(format) = args.format {
+        return Ok(format);
+    }
+
+    let Some(output) = &args.output else {
+        return Ok(Format::Ansi);
+    };
+    let extension = output
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_lowercase);
+    match extension.as_deref() {
+        Some("png") => Ok(Format::Png),
+        Some("svg") => Ok(Format::Svg),
+        _ => Err(anyhow!(
+            "cannot infer the format of {}, use --format",
+            output.display()
+        )),
+    }
+}
+
+/// Shrinks the font size until the padded output fits into the max width and returns the
+/// layout at that size.
+fn fit_font_size(ctx: &mut DrawingContext, segments: &Segments, args: &Args) -> Result<Layout> {
+    let mut font_size = args.font_size;
+    ctx.font_size(font_size);
+    let mut layout = ctx.layout(segments);
+
+    let Some(max_width) = args.max_width else {
+        return Ok(layout);
+    };
+    let Some(available) = max_width
+        .checked_sub(padded(0, args.padding))
+        .filter(|&w| w > 0)
+    else {
+        bail!("padding leaves no space within the max width");
+    };
+
+    if layout.width() > available {
+        // the width scales roughly linearly, so jump close to the target first
+        font_size *= available as f32 / layout.width() as f32;
+        ctx.font_size(font_size);
+        layout = ctx.layout(segments);
+    }
+    while layout.width() > available {
+        font_size *= 0.95;
+        if font_size < 1.0 {
+            bail!("text does not fit into {max_width} pixels");
+        }
+        ctx.font_size(font_size);
+        layout = ctx.layout(segments);
+    }
+
+    Ok(layout)
+}
+
+fn render_canvas(ctx: &mut DrawingContext, layout: &Layout, args: &Args) -> Canvas {
+    let (width, height) = layout.dimensions();
+    let padding = args.padding;
+    let mut canvas = Canvas::new(padded(width, padding), padded(height, padding));
+    if let Some(background) = args.background {
+        canvas.fill(background);
+    }
+
+    let offset = padding as i32;
+    ctx.draw_layout(layout, |(x, y), rgba| {
+        canvas.blend((x + offset, y + offset), rgba)
+    });
+    canvas
+}
+
+fn render_svg(ctx: &mut DrawingContext, layout: &Layout, args: &Args) -> String {
+    let (width, height) = layout.dimensions();
+    let padding = args.padding;
+    let (outer_width, outer_height) = (padded(width, padding), padded(height, padding));
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{outer_width}" height="{outer_height}" viewBox="0 0 {outer_width} {outer_height}">"#
+    );
+    if let Some([r, g, b, a]) = args.background {
+        let opacity = a as f32 / 255.0;
+        svg.push_str(&format!(
+            r##"<rect width="100%" height="100%" fill="#{r:02X}{g:02X}{b:02X}" fill-opacity="{opacity}"/>"##
+        ));
+    }
+    // nest the rendered document to move it by the padding
+    let inner = ctx.render_layout_svg(layout);
+    svg.push_str(&inner.replacen("<svg ", &format!(r#"<svg x="{padding}" y="{padding}" "#), 1));
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Returns the size with the padding added on both sides.
+fn padded(size: u32, padding: u32) -> u32 {
+    size.saturating_add(padding.saturating_mul(2))
+}
+
+/// Parses a padding that fits twice into an `i32`, so offsets and sizes cannot overflow.
+fn parse_padding(value: &str) -> Result<u32> {
+    let padding: u32 = value.parse()?;
+    match padding
+        .checked_mul(2)
+        .filter(|&both| i32::try_from(both).is_ok())
+    {
+        Some(_) => Ok(padding),
+        None => bail!("padding must be at most {}", i32::MAX / 2),
+    }
+}
+
+fn parse_color(value: &str) -> Result<[u8; 4]> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    let channel = |i: usize| -> Result<u8> {
+        let digits = hex
+            .get(i * 2..i * 2 + 2)
+            .ok_or_else(|| anyhow!("invalid hex code"))?;
+        Ok(u8::from_str_radix(digits, 16)?)
+    };
+
+    match hex.len() {
+        6 => Ok([channel(0)?, channel(1)?, channel(2)?, 255]),
+        8 => Ok([channel(0)?, channel(1)?, channel(2)?, channel(3)?]),
+        _ => bail!("expected `RRGGBB` or `RRGGBBAA`"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_color_works() {
+        assert_eq!(parse_color("ff8000").unwrap(), [255, 128, 0, 255]);
+        assert_eq!(parse_color("#FF800080").unwrap(), [255, 128, 0, 128]);
+        assert!(parse_color("#fff").is_err());
+        assert!(parse_color("gg0000").is_err());
+        assert!(parse_color("ää00").is_err());
+    }
+
+    #[test]
+    fn parse_padding_works() {
+        assert_eq!(parse_padding("16").unwrap(), 16);
+        assert_eq!(parse_padding("1073741823").unwrap(), 1073741823);
+        assert!(parse_padding("1073741824").is_err());
+        assert!(parse_padding("4294967295").is_err());
+        assert!(parse_padding("-1").is_err());
+        assert_eq!(padded(u32::MAX - 1, 1073741823), u32::MAX);
+    }
+
+    #[test]
+    fn args_are_valid() {
+        use clap::CommandFactory;
+        Args::command().debug_assert();
+    }
+}
@@ -16,7 +16,7 @@ fn main() {
         let Some(pixel) = canvas.get_pixel_checked(x, y) else {
             return;
         };
-        let mut pixel = pixel.clone();
+        let mut pixel = *pixel;
         pixel.blend(&color);
         canvas.put_pixel(x, y, pixel);
     });
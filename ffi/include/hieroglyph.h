@@ -0,0 +1,213 @@
+#ifndef HIEROGLYPH_H
+#define HIEROGLYPH_H
+
+/* Generated by cbindgen from ffi/lib.rs, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Status code returned by fallible functions.
+ */
+typedef enum {
+  HIEROGLYPH_STATUS_OK = 0,
+  HIEROGLYPH_STATUS_NULL_POINTER,
+  HIEROGLYPH_STATUS_INVALID_UTF8,
+  HIEROGLYPH_STATUS_BUFFER_TOO_SMALL,
+  HIEROGLYPH_STATUS_INVALID_FONT,
+} HieroglyphStatus;
+
+/**
+ * Priority order of the bundled fonts, see `hieroglyph::FontOrder`.
+ */
+typedef enum {
+  HIEROGLYPH_FONT_ORDER_SANS_FIRST = 0,
+  HIEROGLYPH_FONT_ORDER_SERIF_FIRST,
+  HIEROGLYPH_FONT_ORDER_MONO_FIRST,
+} HieroglyphFontOrder;
+
+/**
+ * Options to create a [`HieroglyphContext`] with.
+ */
+typedef struct HieroglyphBuilder HieroglyphBuilder;
+
+/**
+ * A drawing context, see `hieroglyph::DrawingContext`.
+ */
+typedef struct HieroglyphContext HieroglyphContext;
+
+/**
+ * Segmented text ready to be drawn, see `hieroglyph::Segments`.
+ */
+typedef struct HieroglyphSegments HieroglyphSegments;
+
+/**
+ * Callback invoked for every drawn pixel.
+ *
+ * `rgba` points to the four color components of the pixel and is only valid during the call.
+ */
+typedef void (*HieroglyphDrawCallback)(int32_t x, int32_t y, const uint8_t *rgba, void *user_data);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Creates a new builder with default options.
+ */
+HieroglyphBuilder *hieroglyph_builder_new(void);
+
+/**
+ * Frees a builder that was not consumed by [`hieroglyph_builder_build`].
+ *
+ * # Safety
+ * `builder` must be null or a pointer returned by [`hieroglyph_builder_new`].
+ */
+void hieroglyph_builder_free(HieroglyphBuilder *builder);
+
+/**
+ * Sets the font order.
+ *
+ * # Safety
+ * `builder` must be null or a valid builder.
+ */
+HieroglyphStatus hieroglyph_builder_font_order(HieroglyphBuilder *builder,
+                                               HieroglyphFontOrder font_order);
+
+/**
+ * Sets the locale used for shaping.
+ *
+ * # Safety
+ * `builder` must be null or a valid builder and `locale` must point to `len` readable bytes.
+ */
+HieroglyphStatus hieroglyph_builder_locale(HieroglyphBuilder *builder,
+                                           const uint8_t *locale,
+                                           size_t len);
+
+/**
+ * Adds a font that is used before the bundled ones.
+ *
+ * The font data is copied, so the caller keeps ownership of `data`.
+ *
+ * # Safety
+ * `builder` must be null or a valid builder and `data` must point to `len` readable bytes.
+ */
+HieroglyphStatus hieroglyph_builder_pre_font(HieroglyphBuilder *builder,
+                                             const uint8_t *data,
+                                             size_t len);
+
+/**
+ * Consumes the builder and writes the new drawing context to `ctx`.
+ *
+ * Fails with `InvalidFont` if a pre-loaded font cannot be parsed, `ctx` is set to null then.
+ * The builder is consumed unless `NullPointer` is returned.
+ *
+ * # Safety
+ * `builder` must be null or a pointer returned by [`hieroglyph_builder_new`], it must not be
+ * used afterwards.
+ * `ctx` must be null or valid for writes.
+ */
+HieroglyphStatus hieroglyph_builder_build(HieroglyphBuilder *builder, HieroglyphContext **ctx);
+
+/**
+ * Creates a new drawing context with default options.
+ */
+HieroglyphContext *hieroglyph_context_new(void);
+
+/**
+ * Frees a drawing context.
+ *
+ * # Safety
+ * `ctx` must be null or a pointer returned by [`hieroglyph_context_new`] or
+ * [`hieroglyph_builder_build`].
+ */
+void hieroglyph_context_free(HieroglyphContext *ctx);
+
+/**
+ * Sets the font size in pixels.
+ *
+ * # Safety
+ * `ctx` must be null or a valid drawing context.
+ */
+HieroglyphStatus hieroglyph_context_font_size(HieroglyphContext *ctx, float font_size);
+
+/**
+ * Sets the text color.
+ *
+ * # Safety
+ * `ctx` must be null or a valid drawing context.
+ */
+HieroglyphStatus hieroglyph_context_rgba(HieroglyphContext *ctx,
+                                         uint8_t r,
+                                         uint8_t g,
+                                         uint8_t b,
+                                         uint8_t a);
+
+/**
+ * Segments UTF-8 text for drawing.
+ *
+ * The text is copied.
+ * Returns null if `text` is null or not valid UTF-8.
+ *
+ * # Safety
+ * `text` must point to `len` readable bytes.
+ */
+HieroglyphSegments *hieroglyph_segments_new(const uint8_t *text, size_t len);
+
+/**
+ * Frees segments.
+ *
+ * # Safety
+ * `segments` must be null or a pointer returned by [`hieroglyph_segments_new`].
+ */
+void hieroglyph_segments_free(HieroglyphSegments *segments);
+
+/**
+ * Measures the size of the drawn segments in pixels.
+ *
+ * # Safety
+ * All pointers must be null or valid.
+ */
+HieroglyphStatus hieroglyph_context_dimensions(HieroglyphContext *ctx,
+                                               const HieroglyphSegments *segments,
+                                               uint32_t *width,
+                                               uint32_t *height);
+
+/**
+ * Draws the segments into an RGBA buffer of `width * height * 4` bytes.
+ *
+ * The buffer is overwritten with the drawn segments on a transparent background, pixels outside
+ * of it are clipped.
+ * Use [`hieroglyph_context_dimensions`] to get a size that fits the segments exactly.
+ *
+ * # Safety
+ * All pointers must be null or valid and `buffer` must point to `len` writable bytes.
+ */
+HieroglyphStatus hieroglyph_context_render(HieroglyphContext *ctx,
+                                           const HieroglyphSegments *segments,
+                                           uint8_t *buffer,
+                                           size_t len,
+                                           uint32_t width,
+                                           uint32_t height);
+
+/**
+ * Draws the segments by invoking `callback` for every pixel.
+ *
+ * `user_data` is passed to every invocation unchanged.
+ *
+ * # Safety
+ * All pointers must be null or valid.
+ */
+HieroglyphStatus hieroglyph_context_draw(HieroglyphContext *ctx,
+                                         const HieroglyphSegments *segments,
+                                         HieroglyphDrawCallback callback,
+                                         void *user_data);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* HIEROGLYPH_H */
@@ -0,0 +1,381 @@
+//! C bindings for [`hieroglyph`].
+//!
+//! The header for these bindings is generated with [`cbindgen`](https://github.com/mozilla/cbindgen)
+//! and committed as `include/hieroglyph.h`.
+//! The test suite checks that it is up to date, regenerate it with:
+//! ```sh
+//! cbindgen --config ffi/cbindgen.toml --output ffi/include/hieroglyph.h ffi
+//! ```
+//!
+//! All objects are created and freed by this library, every `*_new` or `*_build` function has a
+//! matching `*_free` function.
+//! Strings are passed as pointer and length and have to be valid UTF-8, they don't need to be
+//! null-terminated.
+
+use std::ffi::c_void;
+use std::{ptr, slice};
+
+use hieroglyph::{Canvas, DrawingContext, FontOrder, Segments};
+
+/// Status code returned by fallible functions.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HieroglyphStatus {
+    Ok = 0,
+    NullPointer,
+    InvalidUtf8,
+    BufferTooSmall,
+    InvalidFont,
+}
+
+/// Priority order of the bundled fonts, see `hieroglyph::FontOrder`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HieroglyphFontOrder {
+    SansFirst = 0,
+    SerifFirst,
+    MonoFirst,
+}
+
+/// Options to create a [`HieroglyphContext`] with.
+#[derive(Debug, Default)]
+pub struct HieroglyphBuilder {
+    font_order: FontOrder,
+    locale: Option<String>,
+    pre_fonts: Vec<Vec<u8>>,
+}
+
+/// A drawing context, see `hieroglyph::DrawingContext`.
+#[derive(Debug)]
+pub struct HieroglyphContext(DrawingContext);
+
+/// Segmented text ready to be drawn, see `hieroglyph::Segments`.
+#[derive(Debug)]
+pub struct HieroglyphSegments {
+    segments: Segments<'static>,
+    /// Leaked text the segments borrow from, freed on drop.
+    text: *mut str,
+}
+
+impl Drop for HieroglyphSegments {
+    fn drop(&mut self) {
+        // release the borrow before freeing the text
+        self.segments = Segments::new("");
+        drop(unsafe { Box::from_raw(self.text) });
+    }
+}
+
+/// Callback invoked for every drawn pixel.
+///
+/// `rgba` points to the four color components of the pixel and is only valid during the call.
+pub type HieroglyphDrawCallback =
+    Option<extern "C" fn(x: i32, y: i32, rgba: *const u8, user_data: *mut c_void)>;
+
+/// Creates a new builder with default options.
+#[unsafe(no_mangle)]
+pub extern "C" fn hieroglyph_builder_new() -> *mut HieroglyphBuilder {
+    Box::into_raw(Box::default())
+}
+
+/// Frees a builder that was not consumed by [`hieroglyph_builder_build`].
+///
+/// # Safety
+/// `builder` must be null or a pointer returned by [`hieroglyph_builder_new`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hieroglyph_builder_free(builder: *mut HieroglyphBuilder) {
+    if !builder.is_null() {
+        drop(unsafe { Box::from_raw(builder) });
+    }
+}
+
+/// Sets the font order.
+///
+/// # Safety
+/// `builder` must be null or a valid builder.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hieroglyph_builder_font_order(
+    builder: *mut HieroglyphBuilder,
+    font_order: HieroglyphFontOrder,
+) -> HieroglyphStatus {
+    let Some(builder) = (unsafe { builder.as_mut() }) else {
+        return HieroglyphStatus::NullPointer;
+    };
+    builder.font_order = match font_order {
+        HieroglyphFontOrder::SansFirst => FontOrder::SansFirst,
+        HieroglyphFontOrder::SerifFirst => FontOrder::SerifFirst,
+        HieroglyphFontOrder::MonoFirst => FontOrder::MonoFirst,
+    };
+    HieroglyphStatus::Ok
+}
+
+/// Sets the locale used for shaping.
+///
+/// # Safety
+/// `builder` must be null or a valid builder and `locale` must point to `len` readable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hieroglyph_builder_locale(
+    builder: *mut HieroglyphBuilder,
+    locale: *const u8,
+    len: usize,
+) -> HieroglyphStatus {
+    let Some(builder) = (unsafe { builder.as_mut() }) else {
+        return HieroglyphStatus::NullPointer;
+    };
+    let Some(locale) = (unsafe { bytes(locale, len) }) else {
+        return HieroglyphStatus::NullPointer;
+    };
+    let Ok(locale) = std::str::from_utf8(locale) else {
+        return HieroglyphStatus::InvalidUtf8;
+    };
+    builder.locale = Some(locale.to_string());
+    HieroglyphStatus::Ok
+}
+
+/// Adds a font that is used before the bundled ones.
+///
+/// The font data is copied, so the caller keeps ownership of `data`.
+///
+/// # Safety
+/// `builder` must be null or a valid builder and `data` must point to `len` readable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hieroglyph_builder_pre_font(
+    builder: *mut HieroglyphBuilder,
+    data: *const u8,
+    len: usize,
+) -> HieroglyphStatus {
+    let Some(builder) = (unsafe { builder.as_mut() }) else {
+        return HieroglyphStatus::NullPointer;
+    };
+    let Some(data) = (unsafe { bytes(data, len) }) else {
+        return HieroglyphStatus::NullPointer;
+    };
+    builder.pre_fonts.push(data.to_vec());
+    HieroglyphStatus::Ok
+}
+
+/// Consumes the builder and writes the new drawing context to `ctx`.
+///
+/// Fails with `InvalidFont` if a pre-loaded font cannot be parsed, `ctx` is set to null then.
+/// The builder is consumed unless `NullPointer` is returned.
+///
+/// # Safety
+/// `builder` must be null or a pointer returned by [`hieroglyph_builder_new`], it must not be
+/// used afterwards.
+/// `ctx` must be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hieroglyph_builder_build(
+    builder: *mut HieroglyphBuilder,
+    ctx: *mut *mut HieroglyphContext,
+) -> HieroglyphStatus {
+    let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+        return HieroglyphStatus::NullPointer;
+    };
+    *ctx = ptr::null_mut();
+    if builder.is_null() {
+        return HieroglyphStatus::NullPointer;
+    }
+    let builder = unsafe { Box::from_raw(builder) };
+
+    let mut config = DrawingContext::configure().font_order(builder.font_order);
+    if let Some(locale) = builder.locale {
+        config = config.locale(locale);
+    }
+    if !builder.pre_fonts.is_empty() {
+        config = config.pre_fonts(builder.pre_fonts.into_iter());
+    }
+    match config.try_build() {
+        Ok(built) => {
+            *ctx = Box::into_raw(Box::new(HieroglyphContext(built)));
+            HieroglyphStatus::Ok
+        }
+        // pre-loaded fonts are the only fonts loaded here
+        Err(_) => HieroglyphStatus::InvalidFont,
+    }
+}
+
+/// Creates a new drawing context with default options.
+#[unsafe(no_mangle)]
+pub extern "C" fn hieroglyph_context_new() -> *mut HieroglyphContext {
+    Box::into_raw(Box::new(HieroglyphContext(DrawingContext::new())))
+}
+
+/// Frees a drawing context.
+///
+/// # Safety
+/// `ctx` must be null or a pointer returned by [`hieroglyph_context_new`] or
+/// [`hieroglyph_builder_build`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hieroglyph_context_free(ctx: *mut HieroglyphContext) {
+    if !ctx.is_null() {
+        drop(unsafe { Box::from_raw(ctx) });
+    }
+}
+
+/// Sets the font size in pixels.
+///
+/// # Safety
+/// `ctx` must be null or a valid drawing context.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hieroglyph_context_font_size(
+    ctx: *mut HieroglyphContext,
+    font_size: f32,
+) -> HieroglyphStatus {
+    let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+        return HieroglyphStatus::NullPointer;
+    };
+    ctx.0.font_size(font_size);
+    HieroglyphStatus::Ok
+}
+
+/// Sets the text color.
+///
+/// # Safety
+/// `ctx` must be null or a valid drawing context.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hieroglyph_context_rgba(
+    ctx: *mut HieroglyphContext,
+    r: u8,
+    g: u8,
+    b: u8,
+    a: u8,
+) -> HieroglyphStatus {
+    let Some(ctx) = (unsafe { ctx.as_mut() }) else {
+        return HieroglyphStatus::NullPointer;
+    };
+    ctx.0.rgba(r, g, b, a);
+    HieroglyphStatus::Ok
+}
+
+/// Segments UTF-8 text for drawing.
+///
+/// The text is copied.
+/// Returns null if `text` is null or not valid UTF-8.
+///
+/// # Safety
+/// `text` must point to `len` readable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hieroglyph_segments_new(
+    text: *const u8,
+    len: usize,
+) -> *mut HieroglyphSegments {
+    let Some(Ok(text)) = (unsafe { bytes(text, len) }).map(std::str::from_utf8) else {
+        return ptr::null_mut();
+    };
+    let text: &'static mut str = Box::leak(text.into());
+    let text = ptr::from_mut(text);
+    Box::into_raw(Box::new(HieroglyphSegments {
+        segments: Segments::new(unsafe { &*text }),
+        text,
+    }))
+}
+
+/// Frees segments.
+///
+/// # Safety
+/// `segments` must be null or a pointer returned by [`hieroglyph_segments_new`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hieroglyph_segments_free(segments: *mut HieroglyphSegments) {
+    if !segments.is_null() {
+        drop(unsafe { Box::from_raw(segments) });
+    }
+}
+
+/// Measures the size of the drawn segments in pixels.
+///
+/// # Safety
+/// All pointers must be null or valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hieroglyph_context_dimensions(
+    ctx: *mut HieroglyphContext,
+    segments: *const HieroglyphSegments,
+    width: *mut u32,
+    height: *mut u32,
+) -> HieroglyphStatus {
+    let (Some(ctx), Some(segments)) = (unsafe { ctx.as_mut() }, unsafe { segments.as_ref() })
+    else {
+        return HieroglyphStatus::NullPointer;
+    };
+    let (Some(width), Some(height)) = (unsafe { width.as_mut() }, unsafe { height.as_mut() })
+    else {
+        return HieroglyphStatus::NullPointer;
+    };
+    (*width, *height) = ctx.0.dimensions(&segments.segments);
+    HieroglyphStatus::Ok
+}
+
+/// Draws the segments into an RGBA buffer of `width * height * 4` bytes.
+///
+/// The buffer is overwritten with the drawn segments on a transparent background, pixels outside
+/// of it are clipped.
+/// Use [`hieroglyph_context_dimensions`] to get a size that fits the segments exactly.
+///
+/// # Safety
+/// All pointers must be null or valid and `buffer` must point to `len` writable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hieroglyph_context_render(
+    ctx: *mut HieroglyphContext,
+    segments: *const HieroglyphSegments,
+    buffer: *mut u8,
+    len: usize,
+    width: u32,
+    height: u32,
+) -> HieroglyphStatus {
+    let (Some(ctx), Some(segments)) = (unsafe { ctx.as_mut() }, unsafe { segments.as_ref() })
+    else {
+        return HieroglyphStatus::NullPointer;
+    };
+    if buffer.is_null() && len > 0 {
+        return HieroglyphStatus::NullPointer;
+    }
+    let required = (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|pixels| pixels.checked_mul(4));
+    if required.is_none_or(|required| len < required) {
+        return HieroglyphStatus::BufferTooSmall;
+    }
+
+    let mut canvas = Canvas::new(width, height);
+    ctx.0
+        .draw(&segments.segments, |pos, rgba| canvas.blend(pos, rgba));
+    let pixels = canvas.as_bytes();
+    if !pixels.is_empty() {
+        let buffer = unsafe { slice::from_raw_parts_mut(buffer, pixels.len()) };
+        buffer.copy_from_slice(pixels);
+    }
+    HieroglyphStatus::Ok
+}
+
+/// Draws the segments by invoking `callback` for every pixel.
+///
+/// `user_data` is passed to every invocation unchanged.
+///
+/// # Safety
+/// All pointers must be null or valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn hieroglyph_context_draw(
+    ctx: *mut HieroglyphContext,
+    segments: *const HieroglyphSegments,
+    callback: HieroglyphDrawCallback,
+    user_data: *mut c_void,
+) -> HieroglyphStatus {
+    let (Some(ctx), Some(segments)) = (unsafe { ctx.as_mut() }, unsafe { segments.as_ref() })
+    else {
+        return HieroglyphStatus::NullPointer;
+    };
+    let Some(callback) = callback else {
+        return HieroglyphStatus::NullPointer;
+    };
+    ctx.0.draw(&segments.segments, |(x, y), rgba| {
+        callback(x, y, rgba.as_ptr(), user_data)
+    });
+    HieroglyphStatus::Ok
+}
+
+/// Returns `None` for null pointers, empty slices don't need a valid pointer.
+unsafe fn bytes<'a>(data: *const u8, len: usize) -> Option<&'a [u8]> {
+    match (data.is_null(), len) {
+        (_, 0) => Some(&[]),
+        (true, _) => None,
+        (false, len) => Some(unsafe { slice::from_raw_parts(data, len) }),
+    }
+}
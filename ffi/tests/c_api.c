@@ -0,0 +1,86 @@
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "hieroglyph.h"
+
+#define CHECK(cond)                                                    \
+    do {                                                               \
+        if (!(cond)) {                                                 \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,     \
+                    __LINE__, #cond);                                  \
+            return 1;                                                  \
+        }                                                              \
+    } while (0)
+
+static void count_pixels(int32_t x, int32_t y, const uint8_t *rgba,
+                         void *user_data) {
+    (void)x;
+    (void)y;
+    if (rgba[3] > 0) {
+        *(size_t *)user_data += 1;
+    }
+}
+
+int main(void) {
+    const char *locale = "en";
+    HieroglyphBuilder *builder = hieroglyph_builder_new();
+    CHECK(builder != NULL);
+    CHECK(hieroglyph_builder_font_order(builder, HIEROGLYPH_FONT_ORDER_SERIF_FIRST) ==
+          HIEROGLYPH_STATUS_OK);
+    CHECK(hieroglyph_builder_locale(builder, (const uint8_t *)locale, strlen(locale)) ==
+          HIEROGLYPH_STATUS_OK);
+    HieroglyphContext *ctx = NULL;
+    CHECK(hieroglyph_builder_build(builder, &ctx) == HIEROGLYPH_STATUS_OK);
+    CHECK(ctx != NULL);
+
+    const uint8_t broken_font[] = "not a font";
+    HieroglyphBuilder *broken = hieroglyph_builder_new();
+    CHECK(hieroglyph_builder_pre_font(broken, broken_font, sizeof(broken_font)) ==
+          HIEROGLYPH_STATUS_OK);
+    HieroglyphContext *broken_ctx = NULL;
+    CHECK(hieroglyph_builder_build(broken, &broken_ctx) == HIEROGLYPH_STATUS_INVALID_FONT);
+    CHECK(broken_ctx == NULL);
+
+    CHECK(hieroglyph_context_font_size(ctx, 24.0f) == HIEROGLYPH_STATUS_OK);
+    CHECK(hieroglyph_context_rgba(ctx, 255, 0, 0, 255) == HIEROGLYPH_STATUS_OK);
+
+    const char *text = "Hi \xF0\x9F\xA6\x86";
+    HieroglyphSegments *segments =
+        hieroglyph_segments_new((const uint8_t *)text, strlen(text));
+    CHECK(segments != NULL);
+    CHECK(hieroglyph_segments_new((const uint8_t *)"\xFF", 1) == NULL);
+
+    uint32_t width = 0, height = 0;
+    CHECK(hieroglyph_context_dimensions(ctx, segments, &width, &height) ==
+          HIEROGLYPH_STATUS_OK);
+    CHECK(width > 0 && height > 0);
+
+    size_t len = (size_t)width * height * 4;
+    uint8_t *buffer = malloc(len);
+    CHECK(buffer != NULL);
+    CHECK(hieroglyph_context_render(ctx, segments, buffer, len - 1, width, height) ==
+          HIEROGLYPH_STATUS_BUFFER_TOO_SMALL);
+    CHECK(hieroglyph_context_render(ctx, segments, buffer, SIZE_MAX, UINT32_MAX, UINT32_MAX) ==
+          HIEROGLYPH_STATUS_BUFFER_TOO_SMALL);
+    CHECK(hieroglyph_context_render(ctx, segments, buffer, len, width, height) ==
+          HIEROGLYPH_STATUS_OK);
+    size_t rendered = 0;
+    for (size_t i = 3; i < len; i += 4) {
+        rendered += buffer[i] > 0;
+    }
+    CHECK(rendered > 0);
+    free(buffer);
+
+    size_t drawn = 0;
+    CHECK(hieroglyph_context_draw(ctx, segments, count_pixels, &drawn) ==
+          HIEROGLYPH_STATUS_OK);
+    CHECK(drawn >= rendered);
+    CHECK(hieroglyph_context_draw(ctx, NULL, count_pixels, &drawn) ==
+          HIEROGLYPH_STATUS_NULL_POINTER);
+
+    hieroglyph_segments_free(segments);
+    hieroglyph_context_free(ctx);
+    return 0;
+}
@@ -0,0 +1,56 @@
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+const MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+#[test]
+fn header_is_up_to_date() {
+    let manifest_dir = Path::new(MANIFEST_DIR);
+    let config = cbindgen::Config::from_file(manifest_dir.join("cbindgen.toml")).unwrap();
+    let bindings = cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(manifest_dir.join("lib.rs"))
+        .generate()
+        .unwrap();
+
+    let mut generated = Vec::new();
+    bindings.write(&mut generated);
+    let committed = std::fs::read(manifest_dir.join("include/hieroglyph.h")).unwrap();
+    assert!(
+        generated == committed,
+        "include/hieroglyph.h is outdated, regenerate it with cbindgen"
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn c_program_runs() {
+    let manifest_dir = Path::new(MANIFEST_DIR);
+    // cargo doesn't build the static library for integration tests, so build a fresh one
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("c_api_target");
+    let status = Command::new(env!("CARGO"))
+        .args(["build", "--lib", "--manifest-path"])
+        .arg(manifest_dir.join("Cargo.toml"))
+        .env("CARGO_TARGET_DIR", &target_dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "building the static library failed");
+    let library = target_dir.join("debug/libhieroglyph_ffi.a");
+
+    let program = Path::new(env!("CARGO_TARGET_TMPDIR")).join("c_api");
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(compiler)
+        .arg(manifest_dir.join("tests/c_api.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg(&library)
+        .args(["-lpthread", "-ldl", "-lm", "-o"])
+        .arg(&program)
+        .status()
+        .unwrap();
+    assert!(status.success(), "compiling the C program failed");
+
+    let status = Command::new(&program).status().unwrap();
+    assert!(status.success(), "the C program failed");
+}
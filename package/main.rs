@@ -13,6 +13,7 @@ fn main() -> anyhow::Result<()> {
 }
 
 const INPUT_PATTERN: &str = "fonts/Noto/fonts/*/unhinted/otf/*-Regular.otf";
+const LICENSE_FILES: &[&str] = &["OFL.txt", "LICENSE"];
 const COMPRESSION_LEVEL: i32 = 22;
 
 pub fn compress_fonts() -> Result<()> {
@@ -25,6 +26,21 @@ pub fn compress_fonts() -> Result<()> {
             .ok_or_else(|| anyhow!("file name not found for {}", entry.display()))?;
         let as_name = Path::new(file_name);
         archive.append_path_with_name(&entry, as_name)?;
+
+        // the license of a font is stored as `licenses/<font file stem>.txt`
+        let family_dir = entry.ancestors().nth(3);
+        let license = family_dir
+            .into_iter()
+            .chain([Path::new("fonts/Noto")])
+            .flat_map(|dir| LICENSE_FILES.iter().map(move |file| dir.join(file)))
+            .find(|path| path.is_file());
+        match license {
+            Some(license) => {
+                let as_name = Path::new("licenses").join(as_name.with_extension("txt"));
+                archive.append_path_with_name(license, as_name)?;
+            }
+            None => eprintln!("no license found for {}", entry.display()),
+        }
     }
 
     let spinner = ProgressBar::new_spinner();
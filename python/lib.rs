@@ -0,0 +1,218 @@
+//! Python bindings for [`hieroglyph`].
+//!
+//! The bindings are built with [`maturin`](https://www.maturin.rs):
+//! ```sh
+//! cd python && maturin build --release
+//! ```
+//!
+//! Rendered images expose the NumPy array interface, so `numpy.asarray(image)` returns a
+//! `(height, width, 4)` RGBA array without copying and without NumPy being a dependency.
+
+use std::sync::Mutex;
+
+use hieroglyph::FontOrder;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+
+/// Renders text into RGBA images, see `hieroglyph::DrawingContext`.
+///
+/// The keyword arguments mirror the options of `hieroglyph::DrawingContextBuilder`:
+/// `font_order` is one of `"sans"`, `"serif"` or `"mono"`, `locale` is used for shaping and
+/// `fonts` is a list of font files as `bytes` that are used before the bundled fonts.
+/// Raises `ValueError` if one of the `fonts` cannot be parsed.
+#[pyclass(name = "DrawingContext")]
+struct DrawingContext(Mutex<hieroglyph::DrawingContext>);
+
+/// Text split into segments of text and emojis, see `hieroglyph::Segments`.
+#[pyclass(name = "Segments", frozen)]
+struct Segments(String);
+
+/// A rendered RGBA image with non-premultiplied alpha.
+#[pyclass(name = "Image", frozen)]
+struct Image {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+#[pymethods]
+impl DrawingContext {
+    #[new]
+    #[pyo3(signature = (*, font_order = "sans", locale = None, fonts = None))]
+    fn new(
+        font_order: &str,
+        locale: Option<String>,
+        fonts: Option<Vec<Vec<u8>>>,
+    ) -> PyResult<Self> {
+        let font_order = match font_order {
+            "sans" => FontOrder::SansFirst,
+            "serif" => FontOrder::SerifFirst,
+            "mono" => FontOrder::MonoFirst,
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown font order {other:?}, expected \"sans\", \"serif\" or \"mono\""
+                )));
+            }
+        };
+
+        let mut builder = hieroglyph::DrawingContext::configure().font_order(font_order);
+        if let Some(locale) = locale {
+            builder = builder.locale(locale);
+        }
+        if let Some(fonts) = fonts {
+            builder = builder.pre_fonts(fonts.into_iter());
+        }
+        let context = builder
+            .try_build()
+            .map_err(|error| PyValueError::new_err(error.to_string()))?;
+        Ok(Self(Mutex::new(context)))
+    }
+
+    /// Sets the font size in pixels.
+    fn font_size(&self, font_size: f32) {
+        self.context().font_size(font_size);
+    }
+
+    /// Sets the text color, `a` defaults to fully opaque.
+    #[pyo3(signature = (r, g, b, a = 255))]
+    fn rgba(&self, r: u8, g: u8, b: u8, a: u8) {
+        self.context().rgba(r, g, b, a);
+    }
+
+    /// Returns the width of the rendered segments in pixels.
+    fn width(&self, segments: &Segments) -> u32 {
+        self.context().width(&segments.segments())
+    }
+
+    /// Returns the `(width, height)` of the rendered segments in pixels.
+    fn dimensions(&self, segments: &Segments) -> (u32, u32) {
+        self.context().dimensions(&segments.segments())
+    }
+
+    /// Renders the segments onto a transparent image that fits them exactly.
+    fn render(&self, py: Python<'_>, segments: &Segments) -> Image {
+        let canvas = py.detach(|| self.context().render(&segments.segments()));
+        Image {
+            width: canvas.width(),
+            height: canvas.height(),
+            data: canvas.into_bytes(),
+        }
+    }
+}
+
+impl DrawingContext {
+    fn context(&self) -> std::sync::MutexGuard<'_, hieroglyph::DrawingContext> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[pymethods]
+impl Segments {
+    #[new]
+    fn new(text: String) -> Self {
+        Self(text)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Segments({:?})", self.0)
+    }
+}
+
+impl Segments {
+    fn segments(&self) -> hieroglyph::Segments<'_> {
+        hieroglyph::Segments::new(&self.0)
+    }
+}
+
+#[pymethods]
+impl Image {
+    #[getter]
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[getter]
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The raw RGBA bytes, row by row.
+    #[getter]
+    fn data<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.data)
+    }
+
+    /// NumPy array interface, the array borrows the image data.
+    #[getter]
+    fn __array_interface__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let interface = PyDict::new(py);
+        interface.set_item("version", 3)?;
+        interface.set_item("shape", (self.height, self.width, 4))?;
+        interface.set_item("typestr", "|u1")?;
+        interface.set_item("data", (self.data.as_ptr() as usize, true))?;
+        Ok(interface)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Image(width={}, height={})", self.width, self.height)
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "hieroglyph")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<DrawingContext>()?;
+    m.add_class::<Segments>()?;
+    m.add_class::<Image>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pyo3::types::PyModule;
+
+    use super::*;
+
+    #[test]
+    fn renders_from_python() {
+        Python::attach(|py| {
+            let module = PyModule::new(py, "hieroglyph")?;
+            python_module(&module)?;
+            let globals = PyDict::new(py);
+            globals.set_item("hieroglyph", module)?;
+
+            py.run(
+                cr#"
+ctx = hieroglyph.DrawingContext(font_order="serif", locale="en")
+ctx.font_size(24.0)
+ctx.rgba(255, 0, 0)
+segments = hieroglyph.Segments("Hi 🦆")
+
+width, height = ctx.dimensions(segments)
+assert ctx.width(segments) == width
+image = ctx.render(segments)
+assert (image.width, image.height) == (width, height)
+assert len(image.data) == width * height * 4
+assert any(image.data[3::4])
+
+interface = image.__array_interface__
+assert interface["shape"] == (height, width, 4)
+assert interface["typestr"] == "|u1"
+assert interface["data"][1]
+
+try:
+    hieroglyph.DrawingContext(font_order="cursive")
+    assert False, "unknown font order is accepted"
+except ValueError:
+    pass
+"#,
+                Some(&globals),
+                None,
+            )
+        })
+        .unwrap();
+    }
+}
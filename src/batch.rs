@@ -0,0 +1,122 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, PoisonError};
+
+use rayon::prelude::*;
+
+use crate::{Canvas, DrawingContext, Segments};
+
+impl DrawingContext {
+    /// Renders many segments in parallel, each onto its own [`Canvas`].
+    ///
+    /// The work is spread over the global [`rayon`] thread pool.
+    /// Every worker uses its own `DrawingContext` with the fonts, locale, font size and color of
+    /// this one, sharing its [`FontDatabase`](crate::FontDatabase) so no fonts are loaded again.
+    /// The workers keep their glyph and emoji caches for the whole batch, the caches of this
+    /// context are not used.
+    ///
+    /// The canvases are returned in the same order as the segments.
+    pub fn render_batch(&self, segments: &[Segments]) -> Vec<Canvas> {
+        let pool = ContextPool::new(self);
+        segments
+            .par_iter()
+            .map_init(|| pool.get(), |ctx, segments| ctx.render(segments))
+            .collect()
+    }
+
+    /// Like [`render_batch`](DrawingContext::render_batch), but segments the texts on the workers.
+    pub fn render_batch_str(&self, texts: &[impl AsRef<str> + Sync]) -> Vec<Canvas> {
+        let pool = ContextPool::new(self);
+        texts
+            .par_iter()
+            .map_init(
+                || pool.get(),
+                |ctx, text| ctx.render(&Segments::new(text.as_ref())),
+            )
+            .collect()
+    }
+}
+
+/// Hands out worker contexts and takes them back once a worker is done.
+///
+/// Rayon may call the init function of `map_init` more than once per thread, reusing contexts
+/// keeps their caches warm and limits the number of contexts to the number of busy workers.
+struct ContextPool {
+    fork: Box<dyn Fn() -> DrawingContext + Send + Sync>,
+    idle: Mutex<Vec<DrawingContext>>,
+}
+
+impl ContextPool {
+    fn new(ctx: &DrawingContext) -> Self {
+        Self {
+            fork: Box::new(ctx.fork()),
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn get(&self) -> PooledContext<'_> {
+        let idle = self
+            .idle
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop();
+        PooledContext {
+            ctx: Some(idle.unwrap_or_else(|| (self.fork)())),
+            pool: self,
+        }
+    }
+}
+
+/// A context borrowed from a [`ContextPool`], returned to it on drop.
+struct PooledContext<'p> {
+    ctx: Option<DrawingContext>,
+    pool: &'p ContextPool,
+}
+
+impl Deref for PooledContext<'_> {
+    type Target = DrawingContext;
+
+    fn deref(&self) -> &Self::Target {
+        self.ctx.as_ref().expect("only taken on drop")
+    }
+}
+
+impl DerefMut for PooledContext<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.ctx.as_mut().expect("only taken on drop")
+    }
+}
+
+impl Drop for PooledContext<'_> {
+    fn drop(&mut self) {
+        if let Some(ctx) = self.ctx.take() {
+            let mut idle = self
+                .pool
+                .idle
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            idle.push(ctx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_matches_sequential_rendering() {
+        let mut ctx = DrawingContext::configure().locale("de").build();
+        ctx.font_size(20.0);
+        ctx.rgba(200, 100, 0, 255);
+
+        let texts: Vec<String> = (0..32).map(|i| format!("Tag {i} 🦆")).collect();
+        let segments: Vec<_> = texts.iter().map(|text| Segments::new(text)).collect();
+
+        let batch = ctx.render_batch(&segments);
+        assert_eq!(batch, ctx.render_batch_str(&texts));
+        assert_eq!(batch.len(), texts.len());
+        for (canvas, segments) in batch.iter().zip(&segments) {
+            assert_eq!(canvas, &ctx.render(segments));
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// Upper bounds for the number of entries in the caches of a [`DrawingContext`].
+///
+/// Every limit is optional, [`None`] keeps the cache unbounded, which is the default.
+/// Once a cache is full, the least recently used entry is evicted.
+/// A limit of `0` is treated as `1`.
+///
+/// Set the limits via
+/// [`DrawingContextBuilder::cache_limits`](crate::DrawingContextBuilder::cache_limits):
+/// ```rust
+/// # use hieroglyph::*;
+/// #
+/// let ctx = DrawingContext::configure()
+///     .cache_limits(CacheLimits {
+///         glyphs: Some(4096),
+///         emoji_trees: Some(256),
+///         ..Default::default()
+///     })
+///     .build();
+/// ```
+///
+/// [`DrawingContext`]: crate::DrawingContext
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct CacheLimits {
+    /// Rasterized glyphs and glyph outlines, one entry per glyph, size and subpixel offset.
+    pub glyphs: Option<usize>,
+
+    /// Parsed SVG trees, one entry per emoji.
+    pub emoji_trees: Option<usize>,
+
+    /// Rasterized emojis, one entry per emoji and pixel size.
+    pub emoji_bitmaps: Option<usize>,
+
+    /// Capital letter metrics, one entry per font size.
+    pub capital_info: Option<usize>,
+}
+
+/// Statistics of a single cache.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    /// Number of entries currently held.
+    pub entries: usize,
+
+    /// Number of lookups that found an entry.
+    pub hits: u64,
+
+    /// Number of lookups that had to compute a new entry.
+    pub misses: u64,
+
+    /// Rough estimate of the memory held by the entries in bytes.
+    pub approximate_bytes: usize,
+}
+
+impl CacheStats {
+    /// Returns the share of lookups that found an entry, `0.0` if there were no lookups yet.
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            lookups => self.hits as f64 / lookups as f64,
+        }
+    }
+}
+
+/// Statistics of all caches of a [`DrawingContext`], see
+/// [`DrawingContext::cache_stats`](crate::DrawingContext::cache_stats).
+///
+/// Hits and misses are counted since the context was created, clearing the caches does not reset
+/// them.
+///
+/// [`DrawingContext`]: crate::DrawingContext
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct CacheStatistics {
+    /// See [`CacheLimits::glyphs`].
+    pub glyphs: CacheStats,
+
+    /// See [`CacheLimits::emoji_trees`].
+    ///
+    /// Their memory is only estimated by the size of the SVG source, the parsed trees usually
+    /// take up more.
+    pub emoji_trees: CacheStats,
+
+    /// See [`CacheLimits::emoji_bitmaps`].
+    pub emoji_bitmaps: CacheStats,
+
+    /// See [`CacheLimits::capital_info`].
+    pub capital_info: CacheStats,
+}
+
+/// Tracks the usage of cache keys and decides which ones to evict.
+///
+/// The entries themselves live in the cache this tracks, which removes every key returned by
+/// [`touch`](Lru::touch).
+/// This way caches we don't own, like the `SwashCache`, can be bounded too.
+#[derive(Debug)]
+pub(crate) struct Lru<K> {
+    limit: Option<usize>,
+    ticks: HashMap<K, u64>,
+    order: BTreeMap<u64, K>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K: Hash + Eq + Clone> Lru<K> {
+    pub fn new(limit: Option<usize>) -> Self {
+        Self {
+            limit: limit.map(|limit| limit.max(1)),
+            ticks: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Marks `key` as most recently used and returns the key to evict, if the limit is exceeded.
+    pub fn touch(&mut self, key: &K) -> Option<K> {
+        self.mark(key, true)
+    }
+
+    /// Like [`touch`](Lru::touch), but doesn't count a hit for a key that is still tracked.
+    ///
+    /// Used when an entry is used again right after the lookup that found or created it.
+    pub fn refresh(&mut self, key: &K) -> Option<K> {
+        self.mark(key, false)
+    }
+
+    fn mark(&mut self, key: &K, count_hit: bool) -> Option<K> {
+        self.tick += 1;
+        match self.ticks.insert(key.clone(), self.tick) {
+            Some(previous) => {
+                self.hits += count_hit as u64;
+                self.order.remove(&previous);
+            }
+            None => self.misses += 1,
+        }
+        self.order.insert(self.tick, key.clone());
+
+        if self.ticks.len() <= self.limit? {
+            return None;
+        }
+        let (_, evicted) = self.order.pop_first()?;
+        self.ticks.remove(&evicted);
+        Some(evicted)
+    }
+
+    /// Forgets all keys, the counters are kept.
+    pub fn clear(&mut self) {
+        self.ticks.clear();
+        self.order.clear();
+    }
+
+    pub fn stats(&self, approximate_bytes: usize) -> CacheStats {
+        CacheStats {
+            entries: self.ticks.len(),
+            hits: self.hits,
+            misses: self.misses,
+            approximate_bytes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DrawingContext, Segments};
+
+    #[test]
+    fn lru_evicts_least_recently_used() {
+        let mut lru = Lru::new(Some(2));
+        assert_eq!(lru.touch(&'a'), None);
+        assert_eq!(lru.touch(&'b'), None);
+        assert_eq!(lru.touch(&'a'), None);
+        assert_eq!(lru.touch(&'c'), Some('b'));
+        assert_eq!(lru.touch(&'b'), Some('a'));
+
+        let stats = lru.stats(0);
+        assert_eq!((stats.entries, stats.hits, stats.misses), (2, 1, 4));
+        assert_eq!(stats.hit_rate(), 0.2);
+
+        lru.clear();
+        assert_eq!(lru.stats(0).entries, 0);
+        assert_eq!(Lru::new(Some(0)).touch(&'a'), None);
+        assert_eq!(Lru::new(None).touch(&'a'), None);
+    }
+
+    #[test]
+    fn context_caches_stay_bounded() {
+        let mut ctx = DrawingContext::configure()
+            .cache_limits(CacheLimits {
+                glyphs: Some(4),
+                emoji_trees: Some(1),
+                emoji_bitmaps: Some(1),
+                capital_info: Some(1),
+            })
+            .build();
+
+        let segments = Segments::new("abcdefgh 🦆🚀");
+        ctx.font_size(16.0);
+        ctx.draw(&segments, |_, _| ());
+        ctx.font_size(20.0);
+        ctx.draw(&segments, |_, _| ());
+        ctx.draw(&segments, |_, _| ());
+
+        let stats = ctx.cache_stats();
+        assert_eq!(stats.glyphs.entries, 4);
+        assert_eq!(stats.emoji_trees.entries, 1);
+        assert_eq!(stats.emoji_bitmaps.entries, 1);
+        assert_eq!(stats.capital_info.entries, 1);
+        assert!(stats.glyphs.approximate_bytes > 0);
+        assert!(stats.capital_info.hits > 0);
+
+        ctx.clear_caches();
+        let cleared = ctx.cache_stats();
+        assert_eq!(cleared.glyphs.entries, 0);
+        assert_eq!(cleared.glyphs.approximate_bytes, 0);
+        assert_eq!(cleared.emoji_trees.entries, 0);
+        assert_eq!(cleared.emoji_bitmaps.entries, 0);
+        assert_eq!(cleared.glyphs.misses, stats.glyphs.misses);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{DrawingContext, Error, Segments};
+
+/// `Canvas` is a simple RGBA pixel buffer to render segments onto.
+///
+/// Pixels are stored row by row, four bytes each, with non-premultiplied alpha.
+/// This layout matches what most image libraries and graphics APIs expect, so
+/// [`as_bytes`](Canvas::as_bytes) can be handed over without further conversion.
+///
+/// Use [`DrawingContext::render`] to get a canvas that fits the rendered segments exactly, or
+/// create one via [`new`](Canvas::new) and [`blend`](Canvas::blend) the pixels of
+/// [`draw`](DrawingContext::draw) onto it yourself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    /// Creates a new, fully transparent canvas.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    /// Returns the width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the raw RGBA bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Consumes the canvas and returns the raw RGBA bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.pixels
+    }
+
+    /// Returns the pixel at the given coordinate, or [`None`] if it is out of bounds.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        let i = self.index(x as i32, y as i32)?;
+        let mut pixel = [0; 4];
+        pixel.copy_from_slice(&self.pixels[i..i + 4]);
+        Some(pixel)
+    }
+
+    /// Fills the whole canvas with the given RGBA color.
+    pub fn fill(&mut self, rgba: [u8; 4]) {
+        for pixel in self.pixels.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&rgba);
+        }
+    }
+
+    /// Blends the given RGBA color onto the pixel at `(x, y)`.
+    ///
+    /// The signature fits the callback of [`draw`](DrawingContext::draw), pixels out of bounds
+    /// are ignored.
+    pub fn blend(&mut self, (x, y): (i32, i32), [r, g, b, a]: [u8; 4]) {
+        let Some(i) = self.index(x, y) else { return };
+        if a == 0 {
+            return;
+        }
+
+        let dst = &mut self.pixels[i..i + 4];
+        let src_a = a as f32 / 255.0;
+        let dst_a = dst[3] as f32 / 255.0;
+        let out_a = src_a + dst_a * (1.0 - src_a);
+        for (dst, src) in dst[..3].iter_mut().zip([r, g, b]) {
+            let value = (src as f32 * src_a + *dst as f32 * dst_a * (1.0 - src_a)) / out_a;
+            *dst = value.round() as u8;
+        }
+        dst[3] = (out_a * 255.0).round() as u8;
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        let x = u32::try_from(x).ok().filter(|&x| x < self.width)?;
+        let y = u32::try_from(y).ok().filter(|&y| y < self.height)?;
+        Some((y as usize * self.width as usize + x as usize) * 4)
+    }
+}
+
+impl DrawingContext {
+    /// Renders the provided segments onto a new, transparent [`Canvas`].
+    ///
+    /// The canvas is sized using [`dimensions`](DrawingContext::dimensions), so it fits the
+    /// rendered segments exactly.
+    /// The segments are shaped once into a [`Layout`](crate::Layout), which is measured and then
+    /// drawn.
+    pub fn render(&mut self, segments: &Segments) -> Canvas {
+        let layout = self.layout(segments);
+        self.render_layout(&layout)
+    }
+
+    /// Like [`render`](DrawingContext::render), but fails like
+    /// [`try_draw`](DrawingContext::try_draw) instead of skipping what cannot be drawn.
+    pub fn try_render(&mut self, segments: &Segments) -> Result<Canvas, Error> {
+        let layout = self.try_layout(segments)?;
+        let (width, height) = layout.dimensions();
+        let mut canvas = Canvas::new(width, height);
+        self.try_draw_layout(&layout, |pos, rgba| canvas.blend(pos, rgba))?;
+        Ok(canvas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blending_works() {
+        let mut canvas = Canvas::new(2, 1);
+        canvas.blend((0, 0), [255, 0, 0, 255]);
+        canvas.blend((0, 0), [0, 0, 255, 51]);
+        canvas.blend((1, 0), [0, 255, 0, 51]);
+        canvas.blend((-1, 0), [0, 255, 0, 255]);
+        canvas.blend((2, 0), [0, 255, 0, 255]);
+
+        assert_eq!(canvas.pixel(0, 0), Some([204, 0, 51, 255]));
+        assert_eq!(canvas.pixel(1, 0), Some([0, 255, 0, 51]));
+        assert_eq!(canvas.pixel(2, 0), None);
+        assert_eq!(canvas.as_bytes().len(), 8);
+    }
+
+    #[test]
+    fn render_fits_segments() {
+        let mut ctx = DrawingContext::new();
+        ctx.font_size(20.0);
+
+        let segments = Segments::new("Hello 🦆");
+        let canvas = ctx.render(&segments);
+        assert_eq!((canvas.width(), canvas.height()), ctx.dimensions(&segments));
+
+        assert!(canvas.as_bytes().chunks_exact(4).any(|pixel| pixel[3] > 0));
+    }
+}
@@ -0,0 +1,41 @@
+//! Character coverage of font faces, shared with the build script.
+
+use std::collections::BTreeSet;
+use std::ops::RangeInclusive;
+
+use ttf_parser::Face;
+
+/// Collects the characters of all unicode cmap subtables that map to a glyph.
+pub(crate) fn cmap_chars(face: &Face) -> BTreeSet<char> {
+    let mut chars = BTreeSet::new();
+    for subtable in face
+        .tables()
+        .cmap
+        .into_iter()
+        .flat_map(|cmap| cmap.subtables)
+    {
+        if subtable.is_unicode() {
+            subtable.codepoints(|codepoint| {
+                if subtable
+                    .glyph_index(codepoint)
+                    .is_some_and(|glyph| glyph.0 != 0)
+                {
+                    chars.extend(char::from_u32(codepoint));
+                }
+            });
+        }
+    }
+    chars
+}
+
+/// Joins consecutive characters into ranges, in ascending order.
+pub(crate) fn char_ranges(chars: &BTreeSet<char>) -> Vec<RangeInclusive<char>> {
+    let mut ranges: Vec<RangeInclusive<char>> = Vec::new();
+    for &c in chars {
+        match ranges.last_mut() {
+            Some(range) if *range.end() as u32 + 1 == c as u32 => *range = *range.start()..=c,
+            _ => ranges.push(c..=c),
+        }
+    }
+    ranges
+}
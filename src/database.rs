@@ -0,0 +1,726 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{fs, io};
+
+use cosmic_text::fontdb::{Database, FaceInfo, ID, Source};
+
+use crate::draw::FontIterator;
+use crate::{DrawingContext, Error, FontGroup, FontOrder, FontOrigin, LoadedFont};
+
+/// An immutable font database that can be shared between [`DrawingContext`]s.
+///
+/// Loading the fonts is the most expensive part of creating a `DrawingContext`.
+/// `FontDatabase` keeps the loaded fonts behind an [`Arc`], so cloning it is cheap and it can be
+/// sent to other threads, which then build their own contexts via
+/// [`DrawingContextBuilder::font_database`](crate::DrawingContextBuilder::font_database).
+/// Only the font data is shared, every context gets its own copy of the per-face metadata
+/// (family names, styles, coverage) and keeps its own glyph and emoji caches.
+/// [`DrawingContext::locale`] copies the metadata again.
+///
+/// Get the database of an existing context via [`DrawingContext::font_database`] or build a new
+/// one via [`DrawingContextBuilder::build_font_database`](crate::DrawingContextBuilder::build_font_database).
+///
+/// ```rust
+/// # use hieroglyph::*;
+/// #
+/// let font_database = FontDatabase::new();
+/// let workers: Vec<_> = (0..4)
+///     .map(|_| {
+///         let font_database = font_database.clone();
+///         std::thread::spawn(move || {
+///             let mut ctx = DrawingContext::configure().font_database(font_database).build();
+///             ctx.width(&Segments::new("Hello"))
+///         })
+///     })
+///     .collect();
+/// # for worker in workers {
+/// #     worker.join().unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FontDatabase {
+    database: Arc<Database>,
+    user_fonts: Arc<[LoadedFont]>,
+    parts: Arc<[Part]>,
+    font_order: FontOrder,
+    generation: u64,
+}
+
+/// Source of [`FontDatabase::generation`], starting at `1` so it never matches a default
+/// [`Layout`](crate::Layout).
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+/// Where fonts loaded from the file system are placed relative to the bundled fonts.
+///
+/// Fonts are checked in this order when looking for a glyph:
+/// 1. [`pre_fonts`](crate::DrawingContextBuilder::pre_fonts),
+/// 2. files with [`BeforeBundled`](FontPriority::BeforeBundled), in the order they were added,
+/// 3. the bundled fonts in the configured [`FontOrder`],
+/// 4. files with [`AfterBundled`](FontPriority::AfterBundled), in the order they were added.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum FontPriority {
+    /// Prefer these fonts over the bundled ones, e.g. for brand fonts.
+    #[default]
+    BeforeBundled,
+
+    /// Only use these fonts for characters the bundled fonts cannot render.
+    AfterBundled,
+}
+
+/// A location to load fonts from, see [`FontDatabase::load`].
+///
+/// There is no file system on wasm, so no sources exist there.
+#[derive(Debug, Clone)]
+pub(crate) enum FontSource {
+    #[cfg(not(target_arch = "wasm32"))]
+    File(PathBuf),
+    #[cfg(not(target_arch = "wasm32"))]
+    Dir(PathBuf),
+    #[cfg(not(target_arch = "wasm32"))]
+    System,
+}
+
+/// Faces loaded from a single font, the system or a bundled group.
+///
+/// The database is rebuilt from its parts when fonts are added or removed or the font order
+/// changes, reusing the already parsed faces.
+#[derive(Debug, Clone)]
+struct Part {
+    section: Section,
+    kind: PartKind,
+    ids: Vec<ID>,
+}
+
+/// Position of a [`Part`] in the database, see [`FontPriority`] for the order.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Section {
+    PreFonts,
+    BeforeBundled,
+    Bundled,
+    AfterBundled,
+}
+
+#[derive(Debug, Clone)]
+enum PartKind {
+    User(LoadedFont),
+    #[cfg(not(target_arch = "wasm32"))]
+    System,
+    Bundled(FontGroup),
+}
+
+impl From<FontPriority> for Section {
+    fn from(priority: FontPriority) -> Self {
+        match priority {
+            FontPriority::BeforeBundled => Section::BeforeBundled,
+            FontPriority::AfterBundled => Section::AfterBundled,
+        }
+    }
+}
+
+/// File extensions of fonts picked up from directories.
+#[cfg(not(target_arch = "wasm32"))]
+const FONT_EXTENSIONS: [&str; 4] = ["ttf", "otf", "ttc", "otc"];
+
+impl FontDatabase {
+    /// Loads the bundled fonts using the default [`FontOrder`].
+    pub fn new() -> Self {
+        DrawingContext::configure().build_font_database()
+    }
+
+    /// Returns the number of loaded font faces.
+    pub fn len(&self) -> usize {
+        self.database.len()
+    }
+
+    /// Returns `true` if no font faces are loaded.
+    pub fn is_empty(&self) -> bool {
+        self.database.is_empty()
+    }
+
+    /// Returns the font order the bundled fonts were loaded in.
+    pub fn font_order(&self) -> &FontOrder {
+        &self.font_order
+    }
+
+    /// Returns what was loaded from each font supplied by the user, in the order they are
+    /// checked.
+    ///
+    /// These are the fonts passed via [`pre_fonts`](crate::DrawingContextBuilder::pre_fonts),
+    /// [`font_file`](crate::DrawingContextBuilder::font_file) and
+    /// [`font_dir`](crate::DrawingContextBuilder::font_dir).
+    /// System fonts are not reported.
+    /// Fonts that could not be parsed are included without faces.
+    pub fn user_fonts(&self) -> &[LoadedFont] {
+        &self.user_fonts
+    }
+
+    /// Loads the pre-fonts, font files and bundled fonts in the order of their [`FontPriority`].
+    ///
+    /// The bundled fonts are borrowed from static memory and font files are memory mapped, only
+    /// the pre-fonts are owned.
+    /// Fonts without a readable face are skipped but still reported, the first file or directory
+    /// that cannot be read is returned alongside.
+    pub(crate) fn load(
+        font_order: &FontOrder,
+        pre_fonts: Option<Box<dyn FontIterator>>,
+        font_sources: &[(FontSource, FontPriority)],
+    ) -> (Self, Option<Error>) {
+        let mut font_db = Database::new();
+        let mut parts: Vec<_> = pre_fonts
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .map(|(index, bytes)| {
+                let origin = FontOrigin::PreFont(index);
+                let start = font_db.len();
+                let font = LoadedFont::load(&mut font_db, origin, Source::Binary(Arc::new(bytes)));
+                Part::new(&font_db, start, Section::PreFonts, PartKind::User(font))
+            })
+            .collect();
+        let mut io_errors = Vec::new();
+
+        let mut load_sources = |font_db: &mut Database, parts: &mut Vec<Part>, priority| {
+            font_sources
+                .iter()
+                .filter(|(_, source_priority)| *source_priority == priority)
+                .for_each(|(source, _)| {
+                    load_source(font_db, source, priority.into(), parts, &mut io_errors)
+                });
+        };
+
+        load_sources(&mut font_db, &mut parts, FontPriority::BeforeBundled);
+        for group in unique_groups(font_order) {
+            let start = font_db.len();
+            for &bytes in group.fonts() {
+                font_db.load_font_source(Source::Binary(Arc::new(bytes)));
+            }
+            parts.push(Part::new(
+                &font_db,
+                start,
+                Section::Bundled,
+                PartKind::Bundled(group),
+            ));
+        }
+        load_sources(&mut font_db, &mut parts, FontPriority::AfterBundled);
+
+        let font_database = Self::from_parts(font_db, parts, *font_order);
+        (font_database, io_errors.into_iter().next())
+    }
+
+    fn from_parts(font_db: Database, parts: Vec<Part>, font_order: FontOrder) -> Self {
+        let user_fonts = parts
+            .iter()
+            .filter_map(|part| match &part.kind {
+                PartKind::User(font) => Some(font.clone()),
+                _ => None,
+            })
+            .collect();
+        Self {
+            database: Arc::new(font_db),
+            user_fonts,
+            parts: parts.into(),
+            font_order,
+            generation: NEXT_GENERATION.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    /// Builds a new database from parts with their parsed faces, ordered by their section.
+    ///
+    /// Parts of the same section keep their order, the face data is shared, not copied.
+    fn rebuild(mut parts: Vec<(Section, PartKind, Vec<FaceInfo>)>, font_order: FontOrder) -> Self {
+        parts.sort_by_key(|(section, ..)| *section);
+        let mut font_db = Database::new();
+        let parts = parts
+            .into_iter()
+            .map(|(section, kind, faces)| {
+                let start = font_db.len();
+                faces
+                    .into_iter()
+                    .for_each(|face| font_db.push_face_info(face));
+                Part::new(&font_db, start, section, kind)
+            })
+            .collect();
+        Self::from_parts(font_db, parts, font_order)
+    }
+
+    /// Returns the parts of this database with their parsed faces, to be passed to
+    /// [`rebuild`](FontDatabase::rebuild).
+    fn faces_of_parts(&self) -> Vec<(Section, PartKind, Vec<FaceInfo>)> {
+        self.parts
+            .iter()
+            .map(|part| {
+                let faces = part
+                    .ids
+                    .iter()
+                    .filter_map(|id| self.database.face(*id).cloned())
+                    .collect();
+                (part.section, part.kind.clone(), faces)
+            })
+            .collect()
+    }
+
+    /// Returns a database with the bundled fonts in a different order.
+    ///
+    /// Groups that are already loaded are reused, only the missing ones are parsed.
+    pub(crate) fn with_font_order(&self, font_order: FontOrder) -> Self {
+        let mut bundled = Vec::new();
+        let mut parts: Vec<_> = self
+            .faces_of_parts()
+            .into_iter()
+            .filter_map(|(section, kind, faces)| match kind {
+                PartKind::Bundled(group) => {
+                    bundled.push((group, faces));
+                    None
+                }
+                kind => Some((section, kind, faces)),
+            })
+            .collect();
+
+        for group in unique_groups(&font_order) {
+            let faces = match bundled.iter().position(|(loaded, _)| *loaded == group) {
+                Some(index) => bundled.swap_remove(index).1,
+                None => {
+                    let mut font_db = Database::new();
+                    for &bytes in group.fonts() {
+                        font_db.load_font_source(Source::Binary(Arc::new(bytes)));
+                    }
+                    font_db.faces().cloned().collect()
+                }
+            };
+            parts.push((Section::Bundled, PartKind::Bundled(group), faces));
+        }
+        Self::rebuild(parts, font_order)
+    }
+
+    /// Returns a database with another pre-loaded font, placed after the existing ones.
+    ///
+    /// The font gets the next free [`FontOrigin::PreFont`] index.
+    pub(crate) fn with_pre_font(&self, bytes: Vec<u8>) -> Result<Self, Error> {
+        let index = self
+            .user_fonts
+            .iter()
+            .filter_map(|font| match font.origin {
+                FontOrigin::PreFont(index) => Some(index + 1),
+                FontOrigin::File(_) => None,
+            })
+            .max()
+            .unwrap_or(0);
+        let origin = FontOrigin::PreFont(index);
+        self.with_user_font(Section::PreFonts, origin, Source::Binary(Arc::new(bytes)))
+    }
+
+    /// Returns a database with another font file, placed after the files of the same priority.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn with_font_file(
+        &self,
+        path: PathBuf,
+        priority: FontPriority,
+    ) -> Result<Self, Error> {
+        open_font_file(&path)?;
+        let origin = FontOrigin::File(path.clone());
+        self.with_user_font(priority.into(), origin, Source::File(path))
+    }
+
+    fn with_user_font(
+        &self,
+        section: Section,
+        origin: FontOrigin,
+        source: Source,
+    ) -> Result<Self, Error> {
+        let mut font_db = Database::new();
+        let font = LoadedFont::load(&mut font_db, origin, source);
+        if !font.is_valid() {
+            return Err(invalid_font(&font.origin));
+        }
+        let mut parts = self.faces_of_parts();
+        parts.push((
+            section,
+            PartKind::User(font),
+            font_db.faces().cloned().collect(),
+        ));
+        Ok(Self::rebuild(parts, self.font_order))
+    }
+
+    /// Returns a database without the user font of the given origin, [`None`] if there is none.
+    pub(crate) fn without_font(&self, origin: &FontOrigin) -> Option<Self> {
+        let mut parts = self.faces_of_parts();
+        let index = parts.iter().position(
+            |(_, kind, _)| matches!(kind, PartKind::User(font) if font.origin == *origin),
+        )?;
+        parts.remove(index);
+        Some(Self::rebuild(parts, self.font_order))
+    }
+
+    /// Returns the first user font without a readable face as an error.
+    pub(crate) fn validate(self) -> Result<Self, Error> {
+        match self.user_fonts.iter().find(|font| !font.is_valid()) {
+            Some(font) => Err(invalid_font(&font.origin)),
+            None => Ok(self),
+        }
+    }
+
+    /// Returns the family names of the loaded bundled groups in the given order, without
+    /// duplicates.
+    pub(crate) fn group_families(&self, font_order: &FontOrder) -> Vec<String> {
+        let mut families: Vec<String> = Vec::new();
+        for group in font_order.groups() {
+            let ids = self
+                .parts
+                .iter()
+                .filter(|part| matches!(part.kind, PartKind::Bundled(loaded) if loaded == *group))
+                .flat_map(|part| &part.ids);
+            for id in ids {
+                let Some(face) = self.database.face(*id) else {
+                    continue;
+                };
+                if let Some((family, _)) = face.families.first() &&
+                    !families.contains(family)
+                {
+                    families.push(family.clone());
+                }
+            }
+        }
+        families
+    }
+
+    pub(crate) fn database(&self) -> &Database {
+        &self.database
+    }
+
+    /// Returns a copy of the database for a new font system.
+    ///
+    /// The font data itself is reference counted, so this only copies the face metadata.
+    pub(crate) fn to_database(&self) -> Database {
+        Database::clone(&self.database)
+    }
+
+    /// Identifies this database among all databases built, clones share it.
+    ///
+    /// Rebuilt databases reuse the face ids of older ones, so [`Layout`](crate::Layout)s record
+    /// the generation to tell which faces their glyphs refer to.
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+impl Part {
+    /// Collects the faces loaded into `font_db` since it had `start` faces.
+    ///
+    /// The databases are only ever appended to, so new faces come last.
+    fn new(font_db: &Database, start: usize, section: Section, kind: PartKind) -> Self {
+        let ids = font_db.faces().skip(start).map(|face| face.id).collect();
+        Self { section, kind, ids }
+    }
+}
+
+/// Returns the groups of the font order without repetitions.
+fn unique_groups(font_order: &FontOrder) -> Vec<FontGroup> {
+    let mut groups = Vec::new();
+    for &group in font_order.groups() {
+        if !groups.contains(&group) {
+            groups.push(group);
+        }
+    }
+    groups
+}
+
+fn invalid_font(origin: &FontOrigin) -> Error {
+    match origin {
+        FontOrigin::PreFont(index) => Error::InvalidFont { index: *index },
+        FontOrigin::File(path) => Error::InvalidFontFile { path: path.clone() },
+    }
+}
+
+/// Opens a font file to report why it cannot be read, fontdb only logs files it cannot open.
+#[cfg(not(target_arch = "wasm32"))]
+fn open_font_file(path: &Path) -> Result<(), Error> {
+    fs::File::open(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    Ok(())
+}
+
+/// Loads the fonts of a single source, reporting every font file.
+///
+/// Files and directories that cannot be read are skipped and their errors collected.
+#[cfg(not(target_arch = "wasm32"))]
+fn load_source(
+    font_db: &mut Database,
+    source: &FontSource,
+    section: Section,
+    parts: &mut Vec<Part>,
+    errors: &mut Vec<Error>,
+) {
+    let paths = match source {
+        FontSource::File(path) => vec![path.clone()],
+        FontSource::Dir(path) => {
+            let mut paths = Vec::new();
+            font_files(path, &mut paths, errors);
+            paths
+        }
+        FontSource::System => {
+            let start = font_db.len();
+            font_db.load_system_fonts();
+            parts.push(Part::new(font_db, start, section, PartKind::System));
+            return;
+        }
+    };
+
+    for path in paths {
+        if let Err(error) = open_font_file(&path) {
+            errors.push(error);
+            continue;
+        }
+        let start = font_db.len();
+        let origin = FontOrigin::File(path.clone());
+        let font = LoadedFont::load(font_db, origin, Source::File(path));
+        parts.push(Part::new(font_db, start, section, PartKind::User(font)));
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn load_source(
+    _: &mut Database,
+    source: &FontSource,
+    _: Section,
+    _: &mut Vec<Part>,
+    _: &mut Vec<Error>,
+) {
+    match *source {}
+}
+
+/// Collects the font files in `dir` and its subdirectories, sorted by path.
+///
+/// Symbolic links to directories are not followed, so links pointing back up the tree can't
+/// cause endless recursion.
+/// Directories that cannot be read are skipped and their errors collected.
+#[cfg(not(target_arch = "wasm32"))]
+fn font_files(dir: &Path, paths: &mut Vec<PathBuf>, errors: &mut Vec<Error>) {
+    let entries = fs::read_dir(dir)
+        .and_then(|entries| entries.collect::<io::Result<Vec<_>>>())
+        .map_err(|source| Error::Io {
+            path: dir.to_path_buf(),
+            source,
+        });
+    let mut entries = match entries {
+        Ok(entries) => entries,
+        Err(error) => {
+            errors.push(error);
+            return;
+        }
+    };
+    entries.sort_by_key(|entry| entry.path());
+
+    for entry in entries {
+        let path = entry.path();
+        if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            font_files(&path, paths, errors);
+            continue;
+        }
+        let is_font = path.extension().is_some_and(|extension| {
+            FONT_EXTENSIONS
+                .iter()
+                .any(|font_extension| extension.eq_ignore_ascii_case(font_extension))
+        });
+        if is_font {
+            paths.push(path);
+        }
+    }
+}
+
+impl Default for FontDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::draw::Placement;
+    use crate::{Script, Segments, fonts};
+
+    #[test]
+    fn contexts_share_database() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<FontDatabase>();
+
+        let mut ctx = DrawingContext::new();
+        let font_database = ctx.font_database();
+        assert!(!font_database.is_empty());
+        let expected = ctx.width(&Segments::new("Hi 🦆"));
+
+        let widths: Vec<_> = (0..2)
+            .map(|_| {
+                let font_database = font_database.clone();
+                thread::spawn(move || {
+                    let mut ctx = DrawingContext::configure()
+                        .font_database(font_database.clone())
+                        .build();
+                    assert!(Arc::ptr_eq(
+                        &ctx.font_database().database,
+                        &font_database.database
+                    ));
+                    ctx.width(&Segments::new("Hi 🦆"))
+                })
+            })
+            .collect();
+        for width in widths {
+            assert_eq!(width.join().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn fonts_load_from_files() {
+        let dir = std::env::temp_dir().join(format!("hieroglyph-fonts-{}", std::process::id()));
+        fs::create_dir_all(dir.join("brand")).unwrap();
+        fs::write(dir.join("brand/Brand.TTF"), fonts::NOTO_SANS[0]).unwrap();
+        fs::write(dir.join("broken.otf"), b"not a font").unwrap();
+        fs::write(dir.join("notes.txt"), b"not a font either").unwrap();
+
+        let result = DrawingContext::configure()
+            .font_dir(&dir, FontPriority::BeforeBundled)
+            .try_build_font_database();
+        assert!(
+            matches!(result, Err(Error::InvalidFontFile { path }) if path == dir.join("broken.otf"))
+        );
+        fs::remove_file(dir.join("broken.otf")).unwrap();
+
+        // unreadable files don't stop the rest of the directory from loading
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.join("missing.ttf"), dir.join("a-missing.ttf")).unwrap();
+            std::os::unix::fs::symlink(&dir, dir.join("loop")).unwrap();
+            let (font_database, error) = FontDatabase::load(&FontOrder::default(), None, &[(
+                FontSource::Dir(dir.clone()),
+                FontPriority::BeforeBundled,
+            )]);
+            assert!(
+                matches!(error, Some(Error::Io { path, .. }) if path == dir.join("a-missing.ttf"))
+            );
+            let [font] = font_database.user_fonts() else {
+                panic!("expected a single font");
+            };
+            assert_eq!(font.origin, FontOrigin::File(dir.join("brand/Brand.TTF")));
+            fs::remove_file(dir.join("a-missing.ttf")).unwrap();
+            fs::remove_file(dir.join("loop")).unwrap();
+        }
+
+        let file_source = |face: Option<&cosmic_text::fontdb::FaceInfo>| match face {
+            Some(face) => matches!(&face.source, Source::File(path) if path.starts_with(&dir)),
+            None => false,
+        };
+        let before = DrawingContext::configure()
+            .font_dir(&dir, FontPriority::BeforeBundled)
+            .try_build_font_database()
+            .unwrap();
+        let [font] = before.user_fonts() else {
+            panic!("expected a single font");
+        };
+        assert_eq!(font.origin, FontOrigin::File(dir.join("brand/Brand.TTF")));
+        assert!(file_source(before.database.faces().next()));
+        assert!(!file_source(before.database.faces().last()));
+
+        let after = DrawingContext::configure()
+            .font_file(dir.join("brand/Brand.TTF"), FontPriority::AfterBundled)
+            .build_font_database();
+        assert_eq!(after.len(), before.len());
+        assert!(file_source(after.database.faces().last()));
+        let mut ctx = DrawingContext::configure().font_database(after).build();
+        assert!(ctx.width(&Segments::new("Hi")) > 1);
+
+        let result = DrawingContext::configure()
+            .font_file(dir.join("missing.ttf"), FontPriority::BeforeBundled)
+            .try_build();
+        assert!(matches!(result, Err(Error::Io { .. })));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn font_orders_select_groups() {
+        let serif_only = DrawingContext::configure()
+            .font_order(FontOrder::Custom(&[FontGroup::Serif, FontGroup::Serif]))
+            .build_font_database();
+        assert_eq!(serif_only.len(), fonts::NOTO_SERIF.len());
+
+        let font_database = FontDatabase::new();
+        let family_of = |groups| font_database.group_families(&FontOrder::Custom(groups));
+        let serif = family_of(&[FontGroup::Serif])[0].clone();
+        let mono_first = font_database.group_families(&FontOrder::MonoFirst);
+        assert_eq!(mono_first.first(), family_of(&[FontGroup::Mono]).first());
+        assert!(mono_first.contains(&serif));
+
+        let mut ctx = DrawingContext::configure()
+            .font_database(font_database.clone())
+            .script_font_order(Script::Latin, FontOrder::Custom(&[FontGroup::Serif]))
+            .build();
+        let layout = ctx.layout(&Segments::new("Hi"));
+        for placement in &layout.placements {
+            let Placement::Glyph(glyph) = placement else {
+                panic!("expected only glyphs");
+            };
+            let face = font_database
+                .database
+                .face(glyph.cache_key.font_id)
+                .unwrap();
+            assert_eq!(face.families[0].0, serif);
+        }
+    }
+
+    #[test]
+    fn fonts_change_at_runtime() {
+        let mut ctx = DrawingContext::new();
+        ctx.font_size(24.0);
+        let segments = Segments::new("Hi 🦆");
+        ctx.draw(&segments, |_, _| ());
+        let stats = ctx.cache_stats();
+
+        ctx.locale("de");
+        let after_locale = ctx.cache_stats();
+        assert_eq!(after_locale.glyphs.entries, stats.glyphs.entries);
+        assert_eq!(after_locale.capital_info.entries, 0);
+
+        ctx.font_order(FontOrder::Custom(&[FontGroup::Serif]));
+        let font_database = ctx.font_database();
+        assert_eq!(font_database.len(), fonts::NOTO_SERIF.len());
+        assert_eq!(
+            font_database.font_order(),
+            &FontOrder::Custom(&[FontGroup::Serif])
+        );
+        let after_order = ctx.cache_stats();
+        assert_eq!(after_order.glyphs.entries, 0);
+        assert_eq!(after_order.emoji_trees.entries, stats.emoji_trees.entries);
+        assert_eq!(
+            after_order.emoji_bitmaps.entries,
+            stats.emoji_bitmaps.entries
+        );
+        assert!(ctx.try_layout(&segments).is_ok());
+
+        ctx.add_font(fonts::NOTO_SANS[0]).unwrap();
+        assert!(matches!(
+            ctx.add_font(b"not a font".as_slice()),
+            Err(Error::InvalidFont { index: 1 })
+        ));
+        let user_fonts = ctx.font_database().user_fonts().to_vec();
+        assert_eq!(user_fonts.len(), 1);
+        assert_eq!(user_fonts[0].origin, FontOrigin::PreFont(0));
+        assert_eq!(ctx.font_database().len(), fonts::NOTO_SERIF.len() + 1);
+
+        assert!(ctx.remove_font(&FontOrigin::PreFont(0)));
+        assert!(!ctx.remove_font(&FontOrigin::PreFont(0)));
+        assert!(ctx.font_database().user_fonts().is_empty());
+
+        ctx.font_order(FontOrder::SansFirst);
+        assert_eq!(ctx.font_database().len(), FontDatabase::new().len());
+        let mut redrawn = DrawingContext::new();
+        redrawn.font_size(24.0);
+        assert_eq!(ctx.render(&segments), redrawn.render(&segments));
+    }
+}
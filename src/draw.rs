@@ -1,17 +1,53 @@
 use std::borrow::Cow;
-use std::cmp::max;
 use std::collections::HashMap;
 use std::fmt::Debug;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+use std::sync::Arc;
 
-use cosmic_text::fontdb::Database;
-use cosmic_text::{Attrs, Buffer, FontSystem, Metrics, Shaping, SwashCache};
-use resvg::tiny_skia::{self, Pixmap, PixmapMut};
+use cosmic_text::{Attrs, Buffer, CacheKey, Command, FontSystem, Metrics, Shaping, SwashCache};
+use resvg::tiny_skia::Pixmap;
 use resvg::usvg::{Options, Transform, Tree};
+use twemoji_assets::svg::SvgTwemojiAsset;
 
-use crate::{EmojiSegment, Segment, Segments, TextSegment, fonts};
+use crate::cache::Lru;
+use crate::database::FontSource;
+use crate::family::FontFamilies;
+use crate::{
+    CacheLimits, CacheStatistics, EmojiSegment, Error, FontDatabase, FontOrigin, FontPriority,
+    Layout, Script, Segment, Segments, fonts,
+};
 
 // use the emoji string as key
-type TreeCache = HashMap<&'static str, Tree>;
+type TreeCache = HashMap<&'static str, Arc<Tree>>;
+
+// use the emoji string and the pixel size as key, the pixels are non-premultiplied RGBA
+type EmojiBitmapCache = HashMap<(&'static str, u32), Arc<[[u8; 4]]>>;
+
+/// A single positioned item of a [`Layout`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Placement {
+    Glyph(PlacedGlyph),
+    Emoji(PlacedEmoji),
+}
+
+/// A shaped glyph, `x` and `y` denote its origin on the baseline.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PlacedGlyph {
+    pub cache_key: CacheKey,
+    pub x: i32,
+    pub y: i32,
+    pub color: Option<cosmic_text::Color>,
+}
+
+/// An emoji, `x` and `y` denote the top-left corner of its square of `size` pixels.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PlacedEmoji {
+    pub segment: EmojiSegment,
+    pub x: i32,
+    pub y: i32,
+    pub size: u32,
+}
 
 /// Context for drawing on arbitrary pixel buffers.
 ///
@@ -22,12 +58,21 @@ type TreeCache = HashMap<&'static str, Tree>;
 ///
 /// Create a new `DrawingContext` with [`new`](DrawingContext::new) or customize it via
 /// [`configure`](DrawingContext::configure). 
-/// Some settings can be changed later, while others must be set using the [`DrawingContextBuilder`].
+/// The font size, color, locale, font order and user fonts can be changed later, the other settings
+/// must be set using the [`DrawingContextBuilder`].
 #[derive(Debug)]
 pub struct DrawingContext {
+    pub(crate) font_database: FontDatabase,
     font_system: FontSystem,
     swash_cache: SwashCache,
     tree_cache: TreeCache,
+    emoji_bitmap_cache: EmojiBitmapCache,
+    families: FontFamilies,
+    cache_limits: CacheLimits,
+    glyph_lru: Lru<CacheKey>,
+    tree_lru: Lru<&'static str>,
+    emoji_bitmap_lru: Lru<(&'static str, u32)>,
+    capital_info_lru: Lru<(u32, u32)>,
     font_size: f32,
     color: [u8; 4],
     line_height: f32,
@@ -39,19 +84,24 @@ pub struct DrawingContext {
     /// key properly. Normally this isn't a good idea but considering that
     /// users will only set the font size to specific values, this should be
     /// fine.
-    capital_info: HashMap<(u32, u32), (u32, f32)>,
+    capital_info: HashMap<(u32, u32), Option<(u32, f32)>>,
 }
 
-trait FontIterator: Iterator<Item = Vec<u8>> + Debug {}
+pub(crate) trait FontIterator: Iterator<Item = Vec<u8>> + Debug {}
 impl<I> FontIterator for I where I: Iterator<Item = Vec<u8>> + Debug {}
 
 /// A builder to configure a new [`DrawingContext`].
 ///
 /// Use `DrawingContextBuilder` to customize settings for a new drawing context. 
 /// You can adjust:
-/// - **Font order:** Choose which fonts to check first when rendering text.
+/// - **Font order:** Choose which fonts to check first when rendering text, per script if needed.
 /// - **Pre-loaded fonts:** Add your own fonts to use before the built-in ones.
+/// - **Font files:** Load fonts from files, directories or the system, before or after the
+///   built-in ones.
+/// - **Families:** Select font families by name, per script if needed.
 /// - **Locale:** Set the locale for text shaping and rendering.
+/// - **Font database:** Share already loaded fonts with other contexts.
+/// - **Cache limits:** Bound the memory used by the caches of the context.
 ///
 /// This builder is designed for chaining. 
 /// For example, you can write:
@@ -73,6 +123,10 @@ pub struct DrawingContextBuilder {
     font_order: FontOrder,
     pre_fonts: Option<Box<dyn FontIterator>>,
     locale: Cow<'static, str>,
+    font_sources: Vec<(FontSource, FontPriority)>,
+    families: FontFamilies,
+    font_database: Option<FontDatabase>,
+    cache_limits: CacheLimits,
 }
 
 impl Default for DrawingContextBuilder {
@@ -81,6 +135,10 @@ impl Default for DrawingContextBuilder {
             font_order: Default::default(),
             pre_fonts: Default::default(),
             locale: "en".into(),
+            font_sources: Default::default(),
+            families: Default::default(),
+            font_database: Default::default(),
+            cache_limits: Default::default(),
         }
     }
 }
@@ -98,6 +156,9 @@ impl DrawingContextBuilder {
     /// Sets the pre-loaded fonts to be used before the built-in ones.
     ///
     /// Accepts an iterator over items that can be converted into font data (`Vec<u8>`).
+    /// Every font is validated while loading, [`FontDatabase::user_fonts`] reports the families,
+    /// styles and coverage found in each and [`try_build`](DrawingContextBuilder::try_build)
+    /// fails if one cannot be parsed.
     pub fn pre_fonts(
         self,
         pre_fonts: impl Iterator<Item = impl Into<Vec<u8>> + 'static> + 'static + Debug,
@@ -106,9 +167,116 @@ impl DrawingContextBuilder {
             pre_fonts: Some(Box::new(pre_fonts.map(Into::into))),
             font_order: self.font_order,
             locale: self.locale,
+            font_sources: self.font_sources,
+            families: self.families,
+            font_database: self.font_database,
+            cache_limits: self.cache_limits,
         }
     }
 
+    /// Adds a font file, `.ttc` and `.otc` collections load all of their faces.
+    ///
+    /// The file is memory mapped when the fonts are loaded, so it must not be modified while the
+    /// context is in use.
+    /// See [`FontPriority`] for where the font is placed relative to the bundled fonts.
+    /// Not available on wasm.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn font_file(mut self, path: impl Into<PathBuf>, priority: FontPriority) -> Self {
+        self.font_sources.push((FontSource::File(path.into()), priority));
+        self
+    }
+
+    /// Adds all fonts in a directory and its subdirectories.
+    ///
+    /// Files ending in `.ttf`, `.otf`, `.ttc` or `.otc` are loaded in the order of their paths,
+    /// everything else is ignored.
+    /// Symbolic links to directories are not followed, files that cannot be read are skipped
+    /// without stopping the rest from loading.
+    /// Like [`font_file`](DrawingContextBuilder::font_file), the files are memory mapped.
+    /// Not available on wasm.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn font_dir(mut self, path: impl Into<PathBuf>, priority: FontPriority) -> Self {
+        self.font_sources.push((FontSource::Dir(path.into()), priority));
+        self
+    }
+
+    /// Adds the fonts installed on the system.
+    ///
+    /// Which directories are searched depends on the platform, on Linux fontconfig is used.
+    /// The system fonts are not validated and not reported by [`FontDatabase::user_fonts`].
+    /// Not available on wasm.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn system_fonts(mut self, priority: FontPriority) -> Self {
+        self.font_sources.push((FontSource::System, priority));
+        self
+    }
+
+    /// Adds a font family to use for all scripts, like `"Inter"` or `"Noto Serif"`.
+    ///
+    /// Calling this multiple times builds a chain, for every run of text the first family that
+    /// has a glyph for all of its characters is used.
+    /// Families set via [`script_families`](DrawingContextBuilder::script_families) are checked
+    /// before this chain.
+    /// Bundled and user-supplied fonts can be selected alike, families that are not loaded are
+    /// ignored.
+    /// If no family fits, the fonts are checked in the usual order of the [`FontOrder`].
+    pub fn family(mut self, family: impl Into<String>) -> Self {
+        self.families.push_default(family.into());
+        self
+    }
+
+    /// Sets the chain of font families to use for text of the given script.
+    ///
+    /// ```rust
+    /// # use hieroglyph::*;
+    /// #
+    /// let ctx = DrawingContext::configure()
+    ///     .family("Inter")
+    ///     .script_families(Script::Han, ["Noto Serif JP", "Noto Serif SC"])
+    ///     .script_families(Script::Hiragana, ["Noto Serif JP"])
+    ///     .script_families(Script::Katakana, ["Noto Serif JP"])
+    ///     .script_families(Script::Arabic, ["Noto Sans Arabic"])
+    ///     .build();
+    /// ```
+    ///
+    /// Characters shared between scripts, like spaces and digits, use the chain of the text
+    /// around them.
+    /// Japanese text is split into Han, Hiragana and Katakana runs, so all three scripts need a
+    /// chain to draw it with one family.
+    /// Calling this again for the same script replaces its chain.
+    pub fn script_families(
+        mut self,
+        script: Script,
+        families: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        let families = families.into_iter().map(Into::into).collect();
+        self.families.set_script(script, families);
+        self
+    }
+
+    /// Sets the order of the bundled font groups to use for text of the given script.
+    ///
+    /// ```rust
+    /// # use hieroglyph::*;
+    /// #
+    /// let ctx = DrawingContext::configure()
+    ///     .font_order(FontOrder::SansFirst)
+    ///     .script_font_order(Script::Han, FontOrder::SerifFirst)
+    ///     .script_font_order(Script::Hiragana, FontOrder::SerifFirst)
+    ///     .script_font_order(Script::Katakana, FontOrder::SerifFirst)
+    ///     .build();
+    /// ```
+    ///
+    /// The families of the groups are checked after the ones set via
+    /// [`script_families`](DrawingContextBuilder::script_families) for the same script.
+    /// Groups that are not part of the [`font_order`](DrawingContextBuilder::font_order) are not
+    /// loaded and therefore skipped.
+    /// Calling this again for the same script replaces its order.
+    pub fn script_font_order(mut self, script: Script, font_order: FontOrder) -> Self {
+        self.families.set_script_font_order(script, font_order);
+        self
+    }
+
     /// Sets the locale for text shaping and rendering.
     ///
     /// Accepts any value that can be converted into a `Cow<'static, str>`.
@@ -117,10 +285,73 @@ impl DrawingContextBuilder {
         self
     }
 
+    /// Sets an already loaded [`FontDatabase`] to use instead of loading the fonts again.
+    ///
+    /// The font order, pre-loaded fonts and font files were applied when the database was built,
+    /// so the values set on this builder are ignored.
+    pub fn font_database(mut self, font_database: FontDatabase) -> Self {
+        self.font_database = Some(font_database);
+        self
+    }
+
+    /// Sets the maximum number of entries of the caches, by default they are unbounded.
+    ///
+    /// See [`CacheLimits`] for details.
+    pub fn cache_limits(mut self, cache_limits: CacheLimits) -> Self {
+        self.cache_limits = cache_limits;
+        self
+    }
+
+    /// Consumes the builder and loads the fonts into a [`FontDatabase`].
+    ///
+    /// The font order, pre-loaded fonts and font files are applied, the locale is not part of the 
+    /// database.
+    /// If a database was already set via [`font_database`](DrawingContextBuilder::font_database), 
+    /// that one is returned.
+    pub fn build_font_database(mut self) -> FontDatabase {
+        self.take_font_database().0
+    }
+
+    /// Like [`build_font_database`](DrawingContextBuilder::build_font_database), but fails if a
+    /// user-supplied font cannot be read instead of skipping it.
+    ///
+    /// See [`try_build`](DrawingContextBuilder::try_build) for the possible errors.
+    pub fn try_build_font_database(mut self) -> Result<FontDatabase, Error> {
+        match self.take_font_database() {
+            (_, Some(error)) => Err(error),
+            (font_database, None) => font_database.validate(),
+        }
+    }
+
     /// Consumes the builder and creates a new [`DrawingContext`].
+    ///
+    /// Fonts that cannot be read are skipped.
     pub fn build(self) -> DrawingContext {
         DrawingContext::from_builder(self)
     }
+
+    /// Like [`build`](DrawingContextBuilder::build), but fails if a user-supplied font cannot be
+    /// read instead of skipping it.
+    ///
+    /// Fails with [`Error::InvalidFont`] or [`Error::InvalidFontFile`] if a pre-loaded font or font
+    /// file cannot be parsed and with [`Error::Io`] if a font file or directory cannot be read.
+    /// What was loaded from each font is reported by [`FontDatabase::user_fonts`] of the
+    /// [`font_database`](DrawingContext::font_database).
+    pub fn try_build(mut self) -> Result<DrawingContext, Error> {
+        let font_database = match self.take_font_database() {
+            (_, Some(error)) => return Err(error),
+            (font_database, None) => font_database.validate()?,
+        };
+        Ok(DrawingContext::with_font_database(self, font_database))
+    }
+
+    /// Takes the set database or loads a new one, see [`FontDatabase::load`].
+    fn take_font_database(&mut self) -> (FontDatabase, Option<Error>) {
+        match self.font_database.take() {
+            Some(font_database) => (font_database, None),
+            None => FontDatabase::load(&self.font_order, self.pre_fonts.take(), &self.font_sources),
+        }
+    }
 }
 
 /// Determines the priority order of fonts when rendering characters.
@@ -128,11 +359,66 @@ impl DrawingContextBuilder {
 /// This enum tells the rendering system which fonts to search first for a given character.
 /// By default, [`SansFirst`](FontOrder::SansFirst) is used, meaning sans-serif fonts are 
 /// prioritized before serif fonts.
+/// Use [`script_font_order`](DrawingContextBuilder::script_font_order) to prefer a different
+/// order for some scripts.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
 pub enum FontOrder {
+    /// Sans serif, monospace, serif and other fonts.
     #[default]
     SansFirst,
+
+    /// Serif, sans serif, monospace and other fonts.
     SerifFirst,
+
+    /// Monospace, sans serif, serif and other fonts.
+    MonoFirst,
+
+    /// Only the listed groups in the listed order, groups listed again are ignored.
+    ///
+    /// Leaving out groups keeps their fonts from being loaded at all.
+    Custom(&'static [FontGroup]),
+}
+
+/// A group of bundled fonts, see the [`fonts`](crate::fonts) module.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FontGroup {
+    /// [`NOTO_SANS`](crate::fonts::NOTO_SANS)
+    Sans,
+
+    /// [`NOTO_SERIF`](crate::fonts::NOTO_SERIF)
+    Serif,
+
+    /// [`NOTO_MONO`](crate::fonts::NOTO_MONO)
+    Mono,
+
+    /// [`NOTO_REST`](crate::fonts::NOTO_REST)
+    Rest,
+}
+
+impl FontOrder {
+    /// Returns the groups in the order they are loaded.
+    pub fn groups(&self) -> &[FontGroup] {
+        use FontGroup::*;
+
+        match self {
+            FontOrder::SansFirst => &[Sans, Mono, Serif, Rest],
+            FontOrder::SerifFirst => &[Serif, Sans, Mono, Rest],
+            FontOrder::MonoFirst => &[Mono, Sans, Serif, Rest],
+            FontOrder::Custom(groups) => groups,
+        }
+    }
+}
+
+impl FontGroup {
+    /// Returns the font files of the group.
+    pub fn fonts(self) -> &'static [&'static [u8]] {
+        match self {
+            FontGroup::Sans => &fonts::NOTO_SANS,
+            FontGroup::Serif => &fonts::NOTO_SERIF,
+            FontGroup::Mono => &fonts::NOTO_MONO,
+            FontGroup::Rest => &fonts::NOTO_REST,
+        }
+    }
 }
 
 impl DrawingContext {
@@ -164,37 +450,75 @@ impl DrawingContext {
     /// Typically, you won't call this method directly. 
     /// Instead, use the builder's [`build`](DrawingContextBuilder::build) method, which calls this 
     /// internally and lets you chain configuration calls easily.
-    pub fn from_builder(builder: DrawingContextBuilder) -> Self {
-        use fonts::{NOTO_REST, NOTO_SANS, NOTO_SERIF};
+    pub fn from_builder(mut builder: DrawingContextBuilder) -> Self {
+        let (font_database, _) = builder.take_font_database();
+        Self::with_font_database(builder, font_database)
+    }
 
-        let mut font_db = Database::new();
-        let default_fonts = match builder.font_order {
-            FontOrder::SansFirst => NOTO_SANS.iter().chain(NOTO_SERIF.iter()),
-            FontOrder::SerifFirst => NOTO_SERIF.iter().chain(NOTO_SANS.iter()),
-        }
-        .chain(NOTO_REST.iter())
-        .map(|&bytes| Vec::from(bytes));
-        match builder.pre_fonts {
-            None => default_fonts.for_each(|bytes| font_db.load_font_data(bytes)),
-            Some(pre_fonts) => pre_fonts
-                .map(Into::into)
-                .chain(default_fonts)
-                .for_each(|bytes| font_db.load_font_data(bytes)),
-        }
-        let font_system = FontSystem::new_with_locale_and_db(builder.locale.to_string(), font_db);
+    fn with_font_database(builder: DrawingContextBuilder, font_database: FontDatabase) -> Self {
+        let locale = builder.locale.to_string();
+        let cache_limits = builder.cache_limits;
+        let font_system = FontSystem::new_with_locale_and_db(locale, font_database.to_database());
+        let mut families = builder.families;
+        families.resolve(&font_database);
 
         Self {
             font_size: 12.0,
             color: [0, 0, 0, 255],
 
+            font_database,
             font_system,
             swash_cache: SwashCache::new(),
             tree_cache: TreeCache::new(),
+            emoji_bitmap_cache: EmojiBitmapCache::new(),
+            families,
+            cache_limits,
+            glyph_lru: Lru::new(cache_limits.glyphs),
+            tree_lru: Lru::new(cache_limits.emoji_trees),
+            emoji_bitmap_lru: Lru::new(cache_limits.emoji_bitmaps),
+            capital_info_lru: Lru::new(cache_limits.capital_info),
             line_height: 50.0, // irrelevant for users right now as we do not handle newlines
             capital_info: HashMap::new(),
         }
     }
 
+    /// Returns the [`FontDatabase`] this context was built with.
+    ///
+    /// Pass it to [`DrawingContextBuilder::font_database`] to create more contexts, for example
+    /// one per thread, without loading the fonts again.
+    pub fn font_database(&self) -> FontDatabase {
+        self.font_database.clone()
+    }
+
+    /// Returns the cache limits this context was built with.
+    pub fn cache_limits(&self) -> CacheLimits {
+        self.cache_limits
+    }
+
+    /// Returns a function creating new contexts with the same fonts, families, locale, cache
+    /// limits, font size and color.
+    ///
+    /// The new contexts start with empty caches.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn fork(&self) -> impl Fn() -> DrawingContext + Send + Sync + use<> {
+        let font_database = self.font_database.clone();
+        let locale = self.font_system.locale().to_string();
+        let (font_size, [r, g, b, a]) = (self.font_size, self.color);
+        let families = self.families.clone();
+        let cache_limits = self.cache_limits;
+        move || {
+            let mut ctx = DrawingContext::configure()
+                .font_database(font_database.clone())
+                .locale(locale.clone())
+                .cache_limits(cache_limits)
+                .build();
+            ctx.families = families.clone();
+            ctx.font_size(font_size);
+            ctx.rgba(r, g, b, a);
+            ctx
+        }
+    }
+
     /// Sets the font size.
     pub fn font_size(&mut self, font_size: f32) {
         self.font_size = font_size;
@@ -213,24 +537,109 @@ impl DrawingContext {
         self.rgba(r, g, b, 255);
     }
 
+    /// Sets the locale for text shaping, see [`DrawingContextBuilder::locale`].
+    ///
+    /// The font data stays loaded, but the font system is recreated, so the fonts are parsed
+    /// again when they are next used.
+    /// The cached glyphs are kept, the cached capital letter metrics are dropped.
+    pub fn locale(&mut self, locale: impl Into<Cow<'static, str>>) {
+        let font_db = self.font_database.to_database();
+        self.font_system = FontSystem::new_with_locale_and_db(locale.into().into_owned(), font_db);
+        self.capital_info.clear();
+        self.capital_info_lru.clear();
+    }
+
+    /// Sets the order of the bundled fonts, see [`DrawingContextBuilder::font_order`].
+    ///
+    /// The database is rebuilt from the already loaded fonts, only bundled groups that were not
+    /// loaded before are parsed.
+    /// Like every font change, this drops the cached glyphs and capital letter metrics and
+    /// invalidates [`Layout`]s created before, the emoji caches are kept.
+    pub fn font_order(&mut self, font_order: FontOrder) {
+        let font_database = self.font_database.with_font_order(font_order);
+        self.set_font_database(font_database);
+    }
+
+    /// Adds a font after the pre-loaded fonts, like one more item of
+    /// [`DrawingContextBuilder::pre_fonts`].
+    ///
+    /// Fails with [`Error::InvalidFont`] if the font cannot be parsed, the context is unchanged
+    /// then.
+    /// The font is reported by [`FontDatabase::user_fonts`] with the next free
+    /// [`FontOrigin::PreFont`] index.
+    pub fn add_font(&mut self, font: impl Into<Vec<u8>>) -> Result<(), Error> {
+        let font_database = self.font_database.with_pre_font(font.into())?;
+        self.set_font_database(font_database);
+        Ok(())
+    }
+
+    /// Adds a font file after the files of the same priority, see
+    /// [`DrawingContextBuilder::font_file`].
+    ///
+    /// Fails with [`Error::Io`] or [`Error::InvalidFontFile`] if the file cannot be read, the
+    /// context is unchanged then.
+    /// Not available on wasm.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn add_font_file(
+        &mut self,
+        path: impl Into<PathBuf>,
+        priority: FontPriority,
+    ) -> Result<(), Error> {
+        let font_database = self.font_database.with_font_file(path.into(), priority)?;
+        self.set_font_database(font_database);
+        Ok(())
+    }
+
+    /// Removes the user font with the given origin, as reported by [`FontDatabase::user_fonts`].
+    ///
+    /// Returns `false` if there is no such font.
+    pub fn remove_font(&mut self, origin: &FontOrigin) -> bool {
+        let Some(font_database) = self.font_database.without_font(origin) else {
+            return false;
+        };
+        self.set_font_database(font_database);
+        true
+    }
+
+    /// Switches to another database, dropping everything that refers to faces of the old one.
+    ///
+    /// The face ids of the new database differ, so the cached glyphs, capital letter metrics and
+    /// resolved families are invalid, while the emojis do not depend on fonts at all.
+    fn set_font_database(&mut self, font_database: FontDatabase) {
+        let locale = self.font_system.locale().to_string();
+        self.font_system = FontSystem::new_with_locale_and_db(locale, font_database.to_database());
+        self.families.resolve(&font_database);
+        self.font_database = font_database;
+
+        self.swash_cache.image_cache.clear();
+        self.swash_cache.outline_command_cache.clear();
+        self.glyph_lru.clear();
+        self.capital_info.clear();
+        self.capital_info_lru.clear();
+    }
+
     /// Calculates and returns the width required to render the given segments.
     ///
     /// This method computes the pixel width needed to render the provided [`Segments`] using the 
     /// current settings (font size, color, caches, etc.). 
-    /// It works by "drawing" the segments and tracking the farthest x-coordinate reached. 
-    /// The computation cost is the same as performing a full drawing operation.
+    /// It shapes the segments and tracks the farthest x-coordinate a pixel would be drawn at.
     ///
     /// The returned width can be used for horizontal text alignment. 
-    /// Since this method fully renders the text to measure its width, it is computationally 
-    /// expensive. 
-    /// For the same configuration and segments, the result will remain consistent, so consider 
-    /// caching it externally if it is needed frequently.
+    /// Since this method shapes and rasterizes the text to measure its width, it is 
+    /// computationally expensive. 
+    /// If the same segments are measured and drawn, create a [`Layout`] via
+    /// [`layout`](DrawingContext::layout) once and use it for both.
     pub fn width(&mut self, segments: &Segments) -> u32 {
-        let mut max_x = 0;
-        self.draw(segments, |(x, _), _| {
-            max_x = max_x.max(x);
-        });
-        max_x as u32 + 1 // add 1 so that max_x is within bounds
+        self.layout(segments).width()
+    }
+
+    /// Calculates and returns the width and height required to render the given segments.
+    ///
+    /// Like [`width`](DrawingContext::width), this tracks the farthest x- and y-coordinates a pixel
+    /// would be drawn at, so it comes with the same computation cost.
+    /// Useful to size a canvas or document that should fit the rendered text exactly.
+    pub fn dimensions(&mut self, segments: &Segments) -> (u32, u32) {
+        self.layout(segments).dimensions()
     }
 
     /// Renders the provided segments to an arbitrary image buffer.
@@ -243,137 +652,249 @@ impl DrawingContext {
     ///   1. A tuple `(x, y)` of type `(i32, i32)` representing the pixel's coordinates.
     ///      Coordinates start at the top-left of the image, and using `i32` allows pixels to be 
     ///      drawn slightly left or above the origin.
-    ///   2. An array `[u8; 4]` representing the pixel's raw RGBA color data.
+    ///   2. An array `[u8; 4]` representing the pixel's raw RGBA color data, the alpha is not
+    ///      premultiplied.
     ///      Passing raw pixels lets even image buffers without an alpha channel blend their colors.
     ///
     /// Note that `draw` does not consume the provided [`Segments`] or the `DrawingContext`.
     /// You can call this method multiple times with the same segments without needing to reset
     /// or re-prepare anything.
-    pub fn draw(&mut self, segments: &Segments, mut f: impl FnMut((i32, i32), [u8; 4])) {
+    pub fn draw(&mut self, segments: &Segments, f: impl FnMut((i32, i32), [u8; 4])) {
+        let layout = self.layout(segments);
+        self.draw_layout(&layout, f);
+    }
+
+    /// Like [`draw`](DrawingContext::draw), but fails instead of skipping what cannot be drawn.
+    ///
+    /// Characters without a glyph in any of the loaded fonts, emojis that would be drawn with a
+    /// size of zero and emojis whose SVG cannot be parsed are reported as [`Error`].
+    /// Nothing is drawn if the segments cannot be laid out.
+    pub fn try_draw(
+        &mut self,
+        segments: &Segments,
+        f: impl FnMut((i32, i32), [u8; 4]),
+    ) -> Result<(), Error> {
+        let layout = self.try_layout(segments)?;
+        self.try_draw_layout(&layout, f)
+    }
+
+    /// Shapes the segments and computes where each glyph and emoji ends up.
+    ///
+    /// This is the single source of truth for positioning, every output format builds on the
+    /// returned [`Layout`] to stay consistent with [`draw`](DrawingContext::draw).
+    /// The layout can be kept to measure, hit test and draw the same segments again without
+    /// shaping them another time, see [`Layout`] for details.
+    pub fn layout(&mut self, segments: &Segments) -> Layout {
         let metrics = Metrics::new(self.font_size, self.line_height);
         let attrs = Attrs::new();
-        let mut buffer = Buffer::new_empty(metrics);
-
-        let capital_info = self.capital_info();
-        let (capital_height, _) = capital_info;
-        let mut emoji_buffer =
-            Pixmap::new(max(capital_height, 1), max(capital_height, 1)).expect("never zero size");
-        let mut emoji_buffer = emoji_buffer.as_mut();
-
-        let x_offset = (|| {
-            let segment = segments.as_slice().first()?;
-            let segment = segment.as_text()?;
-            buffer.set_text(
-                &mut self.font_system,
-                segment.as_str(),
-                attrs,
-                Shaping::Advanced,
-            );
-            let run = buffer.layout_runs().next()?;
-            let glyph = run.glyphs.iter().next()?;
-            let glyph = glyph.physical((0., 0.), 1.0);
-            let swash_image = self
-                .swash_cache
-                .get_image(&mut self.font_system, glyph.cache_key)
-                .as_ref()?;
-            Some(swash_image.placement.left)
-        })()
-        .unwrap_or(0);
+        // cosmic-text cannot shape with a line height of zero, so text is skipped at that size
+        let mut buffer = (self.line_height != 0.0).then(|| Buffer::new_empty(metrics));
+        let (capital_height, capital_line_y) = self.capital_info();
 
+        let mut layout = Layout::default();
+        layout.generation = self.font_database.generation();
+        let mut x_offset = 0;
         let mut x_advance = 0;
-        for segment in segments.as_slice() {
+        for (index, segment) in segments.as_slice().iter().enumerate() {
             match segment {
                 Segment::Emoji(emoji_segment) => {
-                    self.draw_emoji_segment(
-                        *emoji_segment,
-                        &mut f,
-                        &mut x_advance,
-                        x_offset,
-                        &mut emoji_buffer,
-                        capital_info,
+                    let x_spacer = (capital_height as f32 * 0.1) as i32;
+                    let x_start = x_advance;
+                    x_advance += x_spacer;
+                    layout.push_emoji(PlacedEmoji {
+                        segment: *emoji_segment,
+                        x: x_advance - x_offset,
+                        y: capital_line_y as i32 - capital_height as i32,
+                        size: capital_height,
+                    });
+                    x_advance += capital_height as i32 + x_spacer;
+                    layout.push_cluster(
+                        index,
+                        0..emoji_segment.emoji().len(),
+                        x_start - x_offset..x_advance - x_offset,
                     );
                 }
                 Segment::Text(text_segment) => {
-                    self.draw_text_segment(
-                        *text_segment,
-                        &mut f,
-                        &mut x_advance,
-                        x_offset,
-                        &mut buffer,
-                        attrs,
-                    );
+                    let Some(buffer) = buffer.as_mut() else {
+                        continue;
+                    };
+                    if self.families.is_empty() {
+                        buffer.set_text(
+                            &mut self.font_system,
+                            text_segment.as_str(),
+                            attrs,
+                            Shaping::Advanced,
+                        );
+                    } else {
+                        let text = text_segment.as_str();
+                        let spans = self.families.spans(&mut self.font_system, text);
+                        buffer.set_rich_text(
+                            &mut self.font_system,
+                            spans,
+                            attrs,
+                            Shaping::Advanced,
+                        );
+                    }
+
+                    // align the ink of the first glyph with the left edge, its bounds are
+                    // reused below so the glyph is only looked up once
+                    let mut first_bounds = None;
+                    if index == 0 {
+                        first_bounds = (|| {
+                            let run = buffer.layout_runs().next()?;
+                            let glyph = run.glyphs.first()?;
+                            let glyph = glyph.physical((0., 0.), 1.0);
+                            Some(self.glyph_image_bounds(glyph.cache_key))
+                        })();
+                        x_offset = first_bounds.flatten().map_or(0, |bounds| bounds.0);
+                    }
+
+                    for run in buffer.layout_runs() {
+                        for glyph in run.glyphs.iter() {
+                            let physical_glyph = glyph.physical((0., 0.), 1.0);
+                            let placed = PlacedGlyph {
+                                cache_key: physical_glyph.cache_key,
+                                x: physical_glyph.x - x_offset + x_advance,
+                                y: run.line_y as i32 + physical_glyph.y,
+                                color: glyph.color_opt,
+                            };
+                            if glyph.glyph_id == 0 && layout.missing_glyph.is_none() {
+                                let text = &text_segment.as_str()[glyph.start..];
+                                layout.missing_glyph = text.chars().next();
+                            }
+                            let bounds = first_bounds
+                                .take()
+                                .unwrap_or_else(|| self.glyph_image_bounds(placed.cache_key));
+                            layout.push_glyph(placed, bounds);
+
+                            let x = (x_advance - x_offset) as f32 + glyph.x;
+                            layout.push_cluster(
+                                index,
+                                glyph.start..glyph.end,
+                                x.floor() as i32..(x + glyph.w).ceil() as i32,
+                            );
+                        }
+
+                        x_advance += run.line_w.ceil() as i32;
+                    }
                 }
             }
         }
+
+        layout
+    }
+
+    /// Like [`layout`](DrawingContext::layout), but fails if a character has no glyph in any of
+    /// the loaded fonts or an emoji would be drawn with a size of zero.
+    pub fn try_layout(&mut self, segments: &Segments) -> Result<Layout, Error> {
+        let layout = self.layout(segments);
+        if let Some(character) = layout.missing_glyph {
+            return Err(Error::MissingGlyph { character });
+        }
+        let empty_emoji = layout.placements.iter().find_map(|placement| match placement {
+            Placement::Emoji(emoji) if emoji.size == 0 => Some(emoji.segment.emoji()),
+            _ => None,
+        });
+        match empty_emoji {
+            Some(emoji) => Err(Error::EmptyEmoji { emoji }),
+            None => Ok(layout),
+        }
+    }
+
+    /// Returns `(left, top, width, height)` of the rasterized glyph relative to its origin.
+    fn glyph_image_bounds(&mut self, cache_key: CacheKey) -> Option<(i32, i32, u32, u32)> {
+        self.track_glyph(cache_key);
+        let placement = self
+            .swash_cache
+            .get_image(&mut self.font_system, cache_key)
+            .as_ref()?
+            .placement;
+        Some((placement.left, -placement.top, placement.width, placement.height))
+    }
+
+    /// Returns the color of a placed glyph, falling back to the current drawing color.
+    pub(crate) fn glyph_color(&self, glyph: &PlacedGlyph) -> cosmic_text::Color {
+        let [r, g, b, a] = self.color;
+        glyph.color.unwrap_or(cosmic_text::Color::rgba(r, g, b, a))
     }
 
-    fn draw_text_segment(
+    /// Returns the scaled outline of a placed glyph, relative to its origin and pointing upwards.
+    pub(crate) fn outline_commands(&mut self, cache_key: CacheKey) -> Option<&[Command]> {
+        self.refresh_glyph(cache_key);
+        self.swash_cache
+            .get_outline_commands(&mut self.font_system, cache_key)
+    }
+
+    pub(crate) fn draw_glyph(
         &mut self,
-        segment: TextSegment,
+        glyph: PlacedGlyph,
         mut f: impl FnMut((i32, i32), [u8; 4]),
-        x_advance: &mut i32,
-        x_offset: i32,
-        buffer: &mut Buffer,
-        attrs: Attrs<'_>,
     ) {
-        buffer.set_text(
+        let glyph_color = self.glyph_color(&glyph);
+        self.refresh_glyph(glyph.cache_key);
+        self.swash_cache.with_pixels(
             &mut self.font_system,
-            segment.as_str(),
-            attrs,
-            Shaping::Advanced,
+            glyph.cache_key,
+            glyph_color,
+            |x, y, color| {
+                f((glyph.x + x, glyph.y + y), color.as_rgba());
+            },
         );
-        for run in buffer.layout_runs() {
-            for glyph in run.glyphs.iter() {
-                let physical_glyph = glyph.physical((0., 0.), 1.0);
-                let glyph_color = glyph.color_opt.unwrap_or(cosmic_text::Color::rgba(
-                    self.color[0],
-                    self.color[1],
-                    self.color[2],
-                    self.color[3],
-                ));
-                let xd = |x| physical_glyph.x + x - x_offset + *x_advance;
-                let yd = |y| run.line_y as i32 + physical_glyph.y + y;
-                self.swash_cache.with_pixels(
-                    &mut self.font_system,
-                    physical_glyph.cache_key,
-                    glyph_color,
-                    |x, y, color| {
-                        f((xd(x), yd(y)), color.as_rgba());
-                    },
-                );
-            }
-
-            *x_advance += run.line_w.ceil() as i32;
-        }
     }
 
-    fn draw_emoji_segment(
+    pub(crate) fn draw_emoji(
         &mut self,
-        segment: EmojiSegment,
+        emoji: PlacedEmoji,
         mut f: impl FnMut((i32, i32), [u8; 4]),
-        x_advance: &mut i32,
-        x_offset: i32,
-        buffer: &mut PixmapMut,
-        (capital_height, capital_line_y): (u32, f32),
-    ) {
-        let tree = self.tree(segment);
-        buffer.fill(tiny_skia::Color::TRANSPARENT);
-        let scale = capital_height as f32 / tree.size().width();
-        let x_spacer = (capital_height as f32 * 0.1) as i32;
-        *x_advance += x_spacer;
+    ) -> Result<(), Error> {
+        let bitmap = self.emoji_bitmap(emoji.segment, emoji.size)?;
+        let pixels = bitmap.iter().zip(pixel_iter(emoji.size, emoji.size));
+        for (&pixel, (x, y, _)) in pixels {
+            f((emoji.x + x as i32, emoji.y + y as i32), pixel);
+        }
+        Ok(())
+    }
+
+    /// Returns the rasterized emoji at the given size, rendering it only if it isn't cached yet.
+    fn emoji_bitmap(&mut self, segment: EmojiSegment, size: u32) -> Result<Arc<[[u8; 4]]>, Error> {
+        let empty = Error::EmptyEmoji {
+            emoji: segment.emoji(),
+        };
+        if size == 0 {
+            return Err(empty);
+        }
+
+        let key = (segment.emoji(), size);
+        if let Some(bitmap) = self.emoji_bitmap_cache.get(&key).cloned() {
+            self.track_emoji_bitmap(key);
+            return Ok(bitmap);
+        }
+
+        let tree = self.shared_tree(segment)?;
+        let mut pixmap = Pixmap::new(size, size).ok_or(empty)?;
+        let scale = size as f32 / tree.size().width();
         let transform = Transform::from_scale(scale, scale);
-        resvg::render(tree, transform, buffer);
-        let pixels = buffer
-            .pixels_mut()
-            .into_iter()
-            .zip(pixel_iter(capital_height, capital_height));
-        for (pixel, (x, y, _)) in pixels {
-            let pixel = [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()];
-            let x = x as i32 - x_offset + *x_advance;
-            let y = y as i32 + capital_line_y as i32 - capital_height as i32;
-            f((x, y), pixel);
+        resvg::render(tree, transform, &mut pixmap.as_mut());
+        let bitmap: Arc<[[u8; 4]]> = pixmap
+            .pixels()
+            .iter()
+            .map(|pixel| {
+                let pixel = pixel.demultiply();
+                [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]
+            })
+            .collect();
+
+        self.emoji_bitmap_cache.insert(key, bitmap.clone());
+        self.track_emoji_bitmap(key);
+        Ok(bitmap)
+    }
+
+    /// Records the use of a cached bitmap and evicts the least recently used one if the cache is
+    /// full.
+    fn track_emoji_bitmap(&mut self, key: (&'static str, u32)) {
+        if let Some(evicted) = self.emoji_bitmap_lru.touch(&key) {
+            self.emoji_bitmap_cache.remove(&evicted);
         }
-        *x_advance += capital_height as i32 + x_spacer;
     }
 
     /// Returns the estimated height and width of a capital letter
@@ -388,44 +909,151 @@ impl DrawingContext {
     ///
     /// Internally, the capital letter `H` is used as a representative glyph, as
     /// it typically has consistent dimensions across fonts.
+    /// It is shaped with the families configured for latin text, falling back to the default chain.
     /// The result is cached and reused for the same font size and line height.
     pub fn capital_info(&mut self) -> (u32, f32) {
+        self.try_capital_info().unwrap_or((0, 0.0))
+    }
+
+    /// Like [`capital_info`](DrawingContext::capital_info), but fails with
+    /// [`Error::MissingGlyph`] if no font can render the `H` instead of returning `(0, 0.0)`.
+    pub fn try_capital_info(&mut self) -> Result<(u32, f32), Error> {
         let key = (self.font_size.to_bits(), self.line_height.to_bits());
+        if let Some(evicted) = self.capital_info_lru.touch(&key) {
+            self.capital_info.remove(&evicted);
+        }
         if let Some(info) = self.capital_info.get(&key) {
-            return info.clone();
+            return info.ok_or(Error::MissingGlyph { character: 'H' });
         };
 
         let info = (|| {
+            if self.line_height == 0.0 {
+                return None;
+            }
             let metrics = Metrics::new(self.font_size, self.line_height);
-            let attrs = Attrs::new();
             let mut buffer = Buffer::new_empty(metrics);
-            buffer.set_text(&mut self.font_system, "H", attrs, Shaping::Advanced);
+            // measure with the family that latin text is drawn with
+            let spans = self.families.spans(&mut self.font_system, "H");
+            buffer.set_rich_text(&mut self.font_system, spans, Attrs::new(), Shaping::Advanced);
             let run = buffer.layout_runs().next()?;
             let glyph = run.glyphs.iter().next()?;
             let glyph = glyph.physical((0., 0.), 1.0);
-            let swash_image = self
-                .swash_cache
-                .get_image(&mut self.font_system, glyph.cache_key)
-                .as_ref()?;
-            let height = swash_image.placement.height;
+            let (_, _, _, height) = self.glyph_image_bounds(glyph.cache_key)?;
             let line_y = run.line_y;
             Some((height, line_y))
-        })()
-        .unwrap_or((0, 0.0));
+        })();
 
-        self.capital_info.insert(key, info.clone());
-        return info;
+        self.capital_info.insert(key, info);
+        info.ok_or(Error::MissingGlyph { character: 'H' })
     }
 
     /// Returns an SVG tree for the given emoji segment.
     ///
     /// This operation is cached, subsequent calls with the same emoji segment will return the 
     /// previously generated tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the SVG of the emoji cannot be parsed, see [`try_tree`](DrawingContext::try_tree).
     pub fn tree(&mut self, segment: EmojiSegment) -> &Tree {
-        self.tree_cache.entry(segment.emoji()).or_insert_with(|| {
+        self.try_tree(segment).expect("twemoji-assets are valid svg")
+    }
+
+    /// Like [`tree`](DrawingContext::tree), but fails with [`Error::InvalidSvg`] instead of
+    /// panicking.
+    pub fn try_tree(&mut self, segment: EmojiSegment) -> Result<&Tree, Error> {
+        self.shared_tree(segment).map(Arc::as_ref)
+    }
+
+    /// Returns the cached SVG tree for the given emoji segment as a shared reference.
+    pub(crate) fn shared_tree(&mut self, segment: EmojiSegment) -> Result<&Arc<Tree>, Error> {
+        let emoji = segment.emoji();
+        if !self.tree_cache.contains_key(emoji) {
             let options = Options::default();
-            Tree::from_str(segment.svg(), &options).expect("twemoji-assets are valid svg")
-        })
+            let tree = Tree::from_str(segment.svg(), &options)
+                .map_err(|source| Error::InvalidSvg { emoji, source })?;
+            self.tree_cache.insert(emoji, Arc::new(tree));
+        }
+        // only track cached trees, so a failed parse leaves no key without an entry behind
+        if let Some(evicted) = self.tree_lru.touch(&emoji) {
+            self.tree_cache.remove(evicted);
+        }
+        Ok(&self.tree_cache[emoji])
+    }
+
+    /// Records the use of a glyph and evicts the least recently used one if the cache is full.
+    fn track_glyph(&mut self, cache_key: CacheKey) {
+        if let Some(evicted) = self.glyph_lru.touch(&cache_key) {
+            self.evict_glyph(evicted);
+        }
+    }
+
+    /// Like [`track_glyph`](Self::track_glyph) for glyphs that were already looked up by the
+    /// layout, so drawing them doesn't count as another hit.
+    fn refresh_glyph(&mut self, cache_key: CacheKey) {
+        if let Some(evicted) = self.glyph_lru.refresh(&cache_key) {
+            self.evict_glyph(evicted);
+        }
+    }
+
+    fn evict_glyph(&mut self, cache_key: CacheKey) {
+        self.swash_cache.image_cache.remove(&cache_key);
+        self.swash_cache.outline_command_cache.remove(&cache_key);
+    }
+
+    /// Empties all caches.
+    ///
+    /// Use this to free memory, for example after rendering a lot of different text.
+    /// The [`FontDatabase`] is not affected.
+    pub fn clear_caches(&mut self) {
+        self.swash_cache.image_cache.clear();
+        self.swash_cache.outline_command_cache.clear();
+        self.tree_cache.clear();
+        self.emoji_bitmap_cache.clear();
+        self.capital_info.clear();
+        self.glyph_lru.clear();
+        self.tree_lru.clear();
+        self.emoji_bitmap_lru.clear();
+        self.capital_info_lru.clear();
+    }
+
+    /// Returns entry counts, hit rates and estimated memory usage of the caches.
+    pub fn cache_stats(&self) -> CacheStatistics {
+        let glyph_bytes = self
+            .swash_cache
+            .image_cache
+            .values()
+            .flatten()
+            .map(|image| size_of_val(image) + image.data.len())
+            .chain(
+                self.swash_cache
+                    .outline_command_cache
+                    .values()
+                    .flatten()
+                    .map(|commands| size_of_val(commands.as_slice())),
+            )
+            .sum();
+        // the parsed trees aren't measured, their SVG source is a cheap stand-in
+        let tree_bytes = self
+            .tree_cache
+            .keys()
+            .filter_map(|emoji| SvgTwemojiAsset::from_emoji(emoji))
+            .map(|asset| asset.len())
+            .sum();
+        let emoji_bitmap_bytes = self
+            .emoji_bitmap_cache
+            .values()
+            .map(|bitmap| size_of_val(bitmap.as_ref()))
+            .sum();
+        let capital_info_bytes =
+            self.capital_info.len() * size_of::<((u32, u32), Option<(u32, f32)>)>();
+
+        CacheStatistics {
+            glyphs: self.glyph_lru.stats(glyph_bytes),
+            emoji_trees: self.tree_lru.stats(tree_bytes),
+            emoji_bitmaps: self.emoji_bitmap_lru.stats(emoji_bitmap_bytes),
+            capital_info: self.capital_info_lru.stats(capital_info_bytes),
+        }
     }
 }
 
@@ -438,15 +1066,12 @@ impl Default for DrawingContext {
 fn pixel_iter(width: u32, height: u32) -> impl Iterator<Item = (u32, u32, usize)> {
     let x_iter = 0..width;
     let y_iter = 0..height;
-    y_iter
-        .enumerate()
-        .map(move |(yi, y)| {
-            x_iter
-                .clone()
-                .enumerate()
-                .map(move |(xi, x)| (x, y, yi * width as usize + xi))
-        })
-        .flatten()
+    y_iter.enumerate().flat_map(move |(yi, y)| {
+        x_iter
+            .clone()
+            .enumerate()
+            .map(move |(xi, x)| (x, y, yi * width as usize + xi))
+    })
 }
 
 #[cfg(test)]
@@ -454,6 +1079,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::zero_prefixed_literal)]
     fn pixel_iter_works() {
         let iter = pixel_iter(3, 4);
         let expected = vec![
@@ -512,4 +1138,49 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn failed_emoji_bitmaps_are_not_tracked() {
+        let mut ctx = DrawingContext::new();
+        let segments = Segments::new("🦆");
+        let [Segment::Emoji(duck)] = segments.as_slice() else {
+            panic!("expected a single emoji");
+        };
+
+        // wider than a pixmap can be
+        assert!(ctx.emoji_bitmap(*duck, u32::MAX).is_err());
+        let stats = ctx.cache_stats();
+        assert_eq!(stats.emoji_bitmaps.entries, 0);
+        assert_eq!(stats.emoji_trees.entries, 1);
+    }
+
+    #[test]
+    fn emoji_bitmaps_are_reused() {
+        let mut ctx = DrawingContext::new();
+        ctx.font_size(30.0);
+        let segments = Segments::new("🦆 🦆");
+
+        let mut first = Vec::new();
+        ctx.draw(&segments, |pos, rgba| first.push((pos, rgba)));
+        let mut second = Vec::new();
+        ctx.draw(&segments, |pos, rgba| second.push((pos, rgba)));
+        assert_eq!(first, second);
+
+        let stats = ctx.cache_stats().emoji_bitmaps;
+        assert_eq!((stats.entries, stats.misses, stats.hits), (1, 1, 3));
+        let (capital_height, _) = ctx.capital_info();
+        assert_eq!(stats.approximate_bytes, (capital_height * capital_height * 4) as usize);
+    }
+
+    #[test]
+    fn drawn_glyphs_are_looked_up_once() {
+        let mut ctx = DrawingContext::new();
+        let segments = Segments::new("H");
+        // measuring the capital letter misses, laying out and drawing "H" is a single hit
+        ctx.draw(&segments, |_, _| ());
+        ctx.draw(&segments, |_, _| ());
+
+        let stats = ctx.cache_stats().glyphs;
+        assert_eq!((stats.entries, stats.misses, stats.hits), (1, 1, 2));
+    }
 }
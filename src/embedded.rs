@@ -0,0 +1,242 @@
+use embedded_graphics_core::Pixel;
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::Point;
+use embedded_graphics_core::pixelcolor::Rgb888;
+
+use crate::{DrawingContext, Segments};
+
+/// 4x4 ordered dithering matrix, values range from 0 to 15.
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Determines how the alpha channel of rendered pixels is mapped onto a [`DrawTarget`].
+///
+/// Draw targets from [`embedded-graphics`](embedded_graphics_core) are write-only and have no
+/// notion of transparency, so each rendered pixel must either be drawn as an opaque color or be
+/// skipped entirely.
+/// Monochrome and low-bit-depth panels usually look best with [`Dither`](AlphaMode::Dither),
+/// while color panels with a known background benefit from [`Blend`](AlphaMode::Blend).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AlphaMode {
+    /// Draws pixels with an alpha value greater or equal to the threshold and skips all others.
+    Threshold(u8),
+
+    /// Draws pixels based on an ordered 4x4 Bayer matrix, anti-aliased edges therefore turn into
+    /// a dither pattern.
+    ///
+    /// Only the alpha channel is dithered, the colors are passed on at full depth and reduced to
+    /// the target's color depth by its color conversion.
+    Dither,
+
+    /// Blends every non-transparent pixel onto the given background color before drawing it.
+    Blend(Rgb888),
+}
+
+impl Default for AlphaMode {
+    fn default() -> Self {
+        AlphaMode::Threshold(128)
+    }
+}
+
+impl AlphaMode {
+    /// Maps a rendered pixel onto an opaque color, or returns [`None`] if it should be skipped.
+    fn apply(self, (x, y): (i32, i32), [r, g, b, a]: [u8; 4]) -> Option<Rgb888> {
+        if a == 0 {
+            return None;
+        }
+
+        match self {
+            AlphaMode::Threshold(threshold) => (a >= threshold).then_some(Rgb888::new(r, g, b)),
+            AlphaMode::Dither => {
+                let level = BAYER_4X4[y.rem_euclid(4) as usize][x.rem_euclid(4) as usize];
+                let threshold = level * 16 + 8;
+                (a > threshold).then_some(Rgb888::new(r, g, b))
+            }
+            AlphaMode::Blend(background) => {
+                use embedded_graphics_core::pixelcolor::RgbColor;
+
+                let blend = |fg: u8, bg: u8| {
+                    let a = a as u16;
+                    ((fg as u16 * a + bg as u16 * (255 - a) + 127) / 255) as u8
+                };
+                Some(Rgb888::new(
+                    blend(r, background.r()),
+                    blend(g, background.g()),
+                    blend(b, background.b()),
+                ))
+            }
+        }
+    }
+}
+
+impl DrawingContext {
+    /// Renders the provided segments onto an [`embedded-graphics`](embedded_graphics_core)
+    /// [`DrawTarget`].
+    ///
+    /// The segments are drawn with their top-left corner at `origin`, using the current settings
+    /// of this context.
+    /// Each pixel is converted into the target's color type via [`Rgb888`], the given
+    /// [`AlphaMode`] decides how transparency is handled.
+    ///
+    /// The segments are rendered onto a [`Canvas`](crate::Canvas) first, so overlapping glyphs
+    /// are blended before the alpha mode is applied, and all pixels are then passed to the target
+    /// in a single [`draw_iter`](DrawTarget::draw_iter) call.
+    /// Errors of the target are returned as is.
+    pub fn draw_target<D>(
+        &mut self,
+        segments: &Segments,
+        origin: Point,
+        mode: AlphaMode,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget,
+        D::Color: From<Rgb888>,
+    {
+        let canvas = self.render(segments);
+        let width = canvas.width().max(1);
+        let pixels = canvas
+            .as_bytes()
+            .chunks_exact(4)
+            .enumerate()
+            .filter_map(|(index, rgba)| {
+                let (x, y) = (index as u32 % width, index as u32 / width);
+                let point = origin + Point::new(x as i32, y as i32);
+                let color = mode.apply((point.x, point.y), [rgba[0], rgba[1], rgba[2], rgba[3]])?;
+                Some(Pixel(point, color.into()))
+            });
+        target.draw_iter(pixels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use embedded_graphics_core::geometry::{OriginDimensions, Size};
+    use embedded_graphics_core::pixelcolor::{BinaryColor, RgbColor};
+
+    use super::*;
+
+    struct Display {
+        size: Size,
+        pixels: Vec<BinaryColor>,
+        calls: usize,
+    }
+
+    impl Display {
+        fn new(width: u32, height: u32) -> Self {
+            Self {
+                size: Size::new(width, height),
+                pixels: vec![BinaryColor::Off; (width * height) as usize],
+                calls: 0,
+            }
+        }
+
+        fn lit(&self) -> usize {
+            self.pixels
+                .iter()
+                .filter(|&&pixel| pixel == BinaryColor::On)
+                .count()
+        }
+    }
+
+    impl OriginDimensions for Display {
+        fn size(&self) -> Size {
+            self.size
+        }
+    }
+
+    impl DrawTarget for Display {
+        type Color = BinaryColor;
+        type Error = Infallible;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Self::Color>>,
+        {
+            self.calls += 1;
+            for Pixel(point, color) in pixels {
+                let (Ok(x), Ok(y)) = (u32::try_from(point.x), u32::try_from(point.y)) else {
+                    continue;
+                };
+                if x < self.size.width && y < self.size.height {
+                    self.pixels[(y * self.size.width + x) as usize] = color;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn alpha_mode_works() {
+        let white = [255, 255, 255, 255];
+        let faint = [255, 255, 255, 100];
+
+        assert_eq!(
+            AlphaMode::Threshold(128).apply((0, 0), white),
+            Some(Rgb888::WHITE)
+        );
+        assert_eq!(AlphaMode::Threshold(128).apply((0, 0), faint), None);
+        assert_eq!(
+            AlphaMode::Threshold(0).apply((0, 0), [255, 255, 255, 0]),
+            None
+        );
+
+        let dithered = (0..4)
+            .flat_map(|y| (0..4).map(move |x| (x, y)))
+            .filter(|&pos| AlphaMode::Dither.apply(pos, faint).is_some())
+            .count();
+        assert_eq!(dithered, 6);
+        assert_eq!(
+            AlphaMode::Dither.apply((-3, -7), white),
+            Some(Rgb888::WHITE)
+        );
+
+        let blended = AlphaMode::Blend(Rgb888::BLACK).apply((0, 0), [255, 0, 0, 51]);
+        assert_eq!(blended, Some(Rgb888::new(51, 0, 0)));
+    }
+
+    #[test]
+    fn emoji_edges_are_blended_with_straight_alpha() {
+        let mut ctx = DrawingContext::new();
+        ctx.font_size(30.0);
+        let mut edges = Vec::new();
+        ctx.draw(&Segments::new("🦆"), |_, rgba @ [.., a]| {
+            if a > 0 && a < 255 {
+                edges.push(rgba);
+            }
+        });
+
+        // premultiplied colors never exceed their alpha, so blending them would darken the edges
+        assert!(edges.iter().any(|&[r, g, b, a]| r.max(g).max(b) > a));
+    }
+
+    #[test]
+    fn draw_target_works() {
+        let mut ctx = DrawingContext::new();
+        ctx.font_size(20.0);
+        ctx.rgb(255, 255, 255);
+        let segments = Segments::new("H🦆");
+
+        let mut threshold = Display::new(64, 32);
+        ctx.draw_target(
+            &segments,
+            Point::zero(),
+            AlphaMode::default(),
+            &mut threshold,
+        )
+        .unwrap();
+        assert!(threshold.lit() > 0);
+        assert_eq!(threshold.calls, 1);
+
+        let mut shifted = Display::new(64, 32);
+        ctx.draw_target(
+            &segments,
+            Point::new(64, 0),
+            AlphaMode::default(),
+            &mut shifted,
+        )
+        .unwrap();
+        assert_eq!(shifted.lit(), 0);
+    }
+}
@@ -0,0 +1,102 @@
+use std::error::Error as StdError;
+use std::path::PathBuf;
+use std::{fmt, io};
+
+use resvg::usvg;
+
+/// Errors reported by the `try_` methods of [`DrawingContext`](crate::DrawingContext) and
+/// [`DrawingContextBuilder`](crate::DrawingContextBuilder).
+///
+/// The methods without the `try_` prefix never fail, they skip whatever cannot be loaded or drawn.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A font passed via
+    /// [`pre_fonts`](crate::DrawingContextBuilder::pre_fonts) contains no readable font face.
+    ///
+    /// `index` is the position of the font in the iterator.
+    InvalidFont { index: usize },
+
+    /// A font file passed via [`font_file`](crate::DrawingContextBuilder::font_file) or found by
+    /// [`font_dir`](crate::DrawingContextBuilder::font_dir) contains no readable font face.
+    InvalidFontFile { path: PathBuf },
+
+    /// A font file or directory could not be read.
+    Io { path: PathBuf, source: io::Error },
+
+    /// The SVG of an emoji could not be parsed.
+    InvalidSvg {
+        emoji: &'static str,
+        source: usvg::Error,
+    },
+
+    /// An emoji would be drawn with a size of zero pixels, usually because the font size is too
+    /// small.
+    EmptyEmoji { emoji: &'static str },
+
+    /// None of the loaded fonts has a glyph for this character.
+    MissingGlyph { character: char },
+
+    /// A [`Layout`](crate::Layout) was drawn after the fonts of the context changed, or with a
+    /// context that doesn't share the fonts it was created with.
+    StaleLayout,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidFont { index } => {
+                write!(f, "pre-loaded font {index} is not a valid font")
+            }
+            Error::InvalidFontFile { path } => {
+                write!(f, "{} is not a valid font", path.display())
+            }
+            Error::Io { path, .. } => write!(f, "could not read {}", path.display()),
+            Error::InvalidSvg { emoji, .. } => write!(f, "could not parse the svg of {emoji}"),
+            Error::EmptyEmoji { emoji } => write!(f, "{emoji} would be drawn with a size of zero"),
+            Error::MissingGlyph { character } => {
+                write!(f, "no font has a glyph for {character:?}")
+            }
+            Error::StaleLayout => write!(f, "the layout was created with different fonts"),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::InvalidSvg { source, .. } => Some(source),
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DrawingContext, Segments, fonts};
+
+    #[test]
+    fn invalid_input_is_reported() {
+        let pre_fonts = vec![fonts::NOTO_SANS[0].to_vec(), b"not a font".to_vec()];
+        let result = DrawingContext::configure()
+            .pre_fonts(pre_fonts.clone().into_iter())
+            .try_build();
+        assert!(matches!(result, Err(Error::InvalidFont { index: 1 })));
+        let mut ctx = DrawingContext::configure()
+            .pre_fonts(pre_fonts.into_iter())
+            .build();
+
+        ctx.font_size(20.0);
+        assert!(ctx.try_render(&Segments::new("Hi 🦆")).is_ok());
+        let result = ctx.try_draw(&Segments::new("a\u{E000}"), |_, _| ());
+        assert!(matches!(result, Err(Error::MissingGlyph { character: '\u{E000}' })));
+
+        ctx.font_size(0.0);
+        let segments = Segments::new("🦆");
+        let result = ctx.try_layout(&segments);
+        assert!(matches!(result, Err(Error::EmptyEmoji { emoji: "🦆" })));
+        ctx.draw(&segments, |_, _| ());
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use cosmic_text::fontdb::{Family, ID, Query};
+use cosmic_text::{Attrs, FontSystem};
+use unicode_script::{Script, UnicodeScript};
+
+use crate::{FontDatabase, FontOrder};
+
+/// Font families to prefer over the fallback of cosmic-text, set via
+/// [`DrawingContextBuilder::family`](crate::DrawingContextBuilder::family),
+/// [`DrawingContextBuilder::script_families`](crate::DrawingContextBuilder::script_families) and
+/// [`DrawingContextBuilder::script_font_order`](crate::DrawingContextBuilder::script_font_order).
+///
+/// Every family is resolved to a face of the font database whenever the database changes,
+/// families that are not loaded are ignored.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FontFamilies {
+    default: Vec<(String, Option<ID>)>,
+    scripts: HashMap<Script, Vec<(String, Option<ID>)>>,
+    font_orders: HashMap<Script, FontOrder>,
+    ordered: HashMap<Script, Vec<(String, Option<ID>)>>,
+}
+
+impl FontFamilies {
+    pub fn is_empty(&self) -> bool {
+        self.default.is_empty() && self.scripts.is_empty() && self.font_orders.is_empty()
+    }
+
+    pub fn push_default(&mut self, family: String) {
+        self.default.push((family, None));
+    }
+
+    pub fn set_script(&mut self, script: Script, families: Vec<String>) {
+        let families = families.into_iter().map(|family| (family, None)).collect();
+        self.scripts.insert(script, families);
+    }
+
+    pub fn set_script_font_order(&mut self, script: Script, font_order: FontOrder) {
+        self.font_orders.insert(script, font_order);
+    }
+
+    /// Looks up the regular face of every family in the database.
+    ///
+    /// The families of the bundled groups of every script font order are collected again, as the
+    /// loaded groups depend on the font order of the database.
+    pub fn resolve(&mut self, font_database: &FontDatabase) {
+        self.ordered = self
+            .font_orders
+            .iter()
+            .map(|(script, font_order)| {
+                let families = font_database.group_families(font_order);
+                (
+                    *script,
+                    families.into_iter().map(|family| (family, None)).collect(),
+                )
+            })
+            .collect();
+
+        let font_db = font_database.database();
+        let families = self
+            .scripts
+            .values_mut()
+            .chain(self.ordered.values_mut())
+            .flatten();
+        for (name, id) in self.default.iter_mut().chain(families) {
+            *id = font_db.query(&Query {
+                families: &[Family::Name(name)],
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Splits the text into runs of a single script and picks the family of each run.
+    ///
+    /// The family of a run is the first one of its script chain, followed by the families of its
+    /// script font order and the default chain, that has a glyph for every character of the run.
+    /// If no family covers the whole run, the first one covering any character is used, so
+    /// cosmic-text only falls back for the missing characters.
+    pub fn spans<'t>(
+        &self,
+        font_system: &mut FontSystem,
+        text: &'t str,
+    ) -> Vec<(&'t str, Attrs<'_>)> {
+        script_runs(text)
+            .into_iter()
+            .map(|(range, script)| {
+                let run = &text[range];
+                let attrs = match self.family(font_system, run, script) {
+                    Some(family) => Attrs::new().family(Family::Name(family)),
+                    None => Attrs::new(),
+                };
+                (run, attrs)
+            })
+            .collect()
+    }
+
+    fn family(&self, font_system: &mut FontSystem, run: &str, script: Script) -> Option<&str> {
+        let chain = [&self.scripts, &self.ordered]
+            .into_iter()
+            .filter_map(|chains| chains.get(&script))
+            .flatten()
+            .chain(&self.default)
+            .filter_map(|(name, id)| Some((name.as_str(), (*id)?)));
+
+        let chars: Vec<char> = run.chars().filter(|c| !c.is_whitespace()).collect();
+        let mut partial = None;
+        for (name, id) in chain {
+            let Some(font) = font_system.get_font(id) else {
+                continue;
+            };
+            let charmap = font.as_swash().charmap();
+            let covered = chars.iter().filter(|&&c| charmap.map(c) != 0).count();
+            if covered == chars.len() {
+                return Some(name);
+            }
+            if covered > 0 {
+                partial = partial.or(Some(name));
+            }
+        }
+        partial
+    }
+}
+
+/// Splits the text into byte ranges of a single script.
+///
+/// Characters shared by many scripts, like spaces, digits and combining marks, join the run they
+/// are found in.
+fn script_runs(text: &str) -> Vec<(Range<usize>, Script)> {
+    let is_shared = |script| matches!(script, Script::Common | Script::Inherited | Script::Unknown);
+
+    let mut runs: Vec<(Range<usize>, Script)> = Vec::new();
+    for (start, c) in text.char_indices() {
+        let end = start + c.len_utf8();
+        let script = c.script();
+        match runs.last_mut() {
+            Some((range, run_script)) if is_shared(script) || script == *run_script => {
+                range.end = end;
+            }
+            Some((range, run_script)) if is_shared(*run_script) => {
+                range.end = end;
+                *run_script = script;
+            }
+            _ => runs.push((start..end, script)),
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw::Placement;
+    use crate::{DrawingContext, FontDatabase, Segments};
+
+    #[test]
+    fn script_runs_work() {
+        let runs = script_runs("1 Hello, 世界 and العربية!");
+        let runs: Vec<_> = runs
+            .into_iter()
+            .map(|(range, script)| (&"1 Hello, 世界 and العربية!"[range], script))
+            .collect();
+        assert_eq!(runs, vec![
+            ("1 Hello, ", Script::Latin),
+            ("世界 ", Script::Han),
+            ("and ", Script::Latin),
+            ("العربية!", Script::Arabic),
+        ]);
+        assert!(script_runs("").is_empty());
+    }
+
+    #[test]
+    fn families_are_selected_per_script() {
+        let font_database = FontDatabase::new();
+        let mut font_system =
+            FontSystem::new_with_locale_and_db("en".into(), font_database.to_database());
+        let faces: Vec<_> = font_system.db().faces().map(|face| face.id).collect();
+        let mut latin_families = Vec::new();
+        for id in faces {
+            let family = font_system.db().face(id).unwrap().families[0].0.clone();
+            let font = font_system.get_font(id).unwrap();
+            let charmap = font.as_swash().charmap();
+            if "Hi".chars().all(|c| charmap.map(c) != 0) && !latin_families.contains(&family) {
+                latin_families.push(family);
+            }
+        }
+        let [first, second, ..] = latin_families.as_slice() else {
+            panic!("expected at least two families covering latin");
+        };
+
+        let families_of = |ctx: &mut DrawingContext| {
+            let layout = ctx.layout(&Segments::new("Hi"));
+            let mut families: Vec<_> = layout
+                .placements
+                .iter()
+                .filter_map(|placement| match placement {
+                    Placement::Glyph(glyph) => Some(glyph.cache_key.font_id),
+                    Placement::Emoji(_) => None,
+                })
+                .map(|id| font_system.db().face(id).unwrap().families[0].0.clone())
+                .collect();
+            families.dedup();
+            families
+        };
+
+        let mut ctx = DrawingContext::configure()
+            .font_database(font_database.clone())
+            .family(first.as_str())
+            .build();
+        assert_eq!(families_of(&mut ctx), vec![first.clone()]);
+
+        let mut ctx = DrawingContext::configure()
+            .font_database(font_database)
+            .family("Not A Loaded Family")
+            .family(first.as_str())
+            .script_families(Script::Latin, [second.as_str()])
+            .build();
+        assert_eq!(families_of(&mut ctx), vec![second.clone()]);
+    }
+}
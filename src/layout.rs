@@ -0,0 +1,244 @@
+use std::ops::Range;
+
+use crate::draw::{PlacedEmoji, PlacedGlyph, Placement};
+use crate::{Canvas, DrawingContext, Error};
+
+/// Segments shaped and positioned by [`DrawingContext::layout`].
+///
+/// Shaping is the expensive part of [`draw`](DrawingContext::draw) and
+/// [`width`](DrawingContext::width), both shape the segments on every call.
+/// A `Layout` holds the result, so the same text can be measured, hit tested and drawn as often as
+/// needed without shaping it again:
+/// ```rust
+/// # use hieroglyph::*;
+/// #
+/// let mut ctx = DrawingContext::new();
+/// ctx.font_size(32.0);
+/// let layout = ctx.layout(&Segments::new("Hello 🌍"));
+///
+/// let (width, height) = layout.dimensions();
+/// ctx.rgb(0, 0, 0);
+/// ctx.draw_layout(&layout, |(x, y), rgba| { /* draw shadow */ });
+/// ctx.rgb(255, 255, 255);
+/// ctx.draw_layout(&layout, |(x, y), rgba| { /* draw text */ });
+/// ```
+///
+/// The font size is fixed when the layout is created, the color is taken from the context when
+/// drawing.
+/// A layout refers to the fonts of the context that created it, so draw it only with that context
+/// or one sharing its [`FontDatabase`](crate::FontDatabase).
+/// Changing the fonts of the context, e.g. via [`font_order`](DrawingContext::font_order),
+/// invalidates the layout: [`draw_layout`](DrawingContext::draw_layout) draws nothing and
+/// [`try_draw_layout`](DrawingContext::try_draw_layout) fails with [`Error::StaleLayout`] then.
+#[derive(Debug, Clone, Default)]
+pub struct Layout {
+    pub(crate) placements: Vec<Placement>,
+    clusters: Vec<Cluster>,
+    pub(crate) missing_glyph: Option<char>,
+    pub(crate) generation: u64,
+    max_x: i32,
+    max_y: i32,
+}
+
+/// A cluster of a [`Layout`], the smallest part of the text that can be hit.
+///
+/// Usually a cluster is a single character, but ligatures or combining marks may join several
+/// characters and every emoji is a cluster of its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cluster {
+    /// Index of the segment in [`Segments::as_slice`](crate::Segments::as_slice).
+    pub segment: usize,
+
+    /// Byte range of the cluster within the text of its segment.
+    pub bytes: Range<usize>,
+
+    /// Horizontal pixel range covered by the cluster, including the spacing around emojis.
+    pub x: Range<i32>,
+}
+
+impl Layout {
+    /// Returns the width in pixels, same as [`DrawingContext::width`].
+    pub fn width(&self) -> u32 {
+        self.max_x as u32 + 1 // add 1 so that max_x is within bounds
+    }
+
+    /// Returns the height in pixels.
+    pub fn height(&self) -> u32 {
+        self.max_y as u32 + 1
+    }
+
+    /// Returns the width and height in pixels, same as [`DrawingContext::dimensions`].
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width(), self.height())
+    }
+
+    /// Returns all clusters from left to right in logical order.
+    pub fn clusters(&self) -> &[Cluster] {
+        &self.clusters
+    }
+
+    /// Returns the cluster at the given pixel, [`None`] if the pixel is outside of the layout.
+    ///
+    /// Every pixel column within the [`width`](Layout::width) hits a cluster, the exact
+    /// shape of the glyphs is not taken into account.
+    pub fn hit_test(&self, x: i32, y: i32) -> Option<&Cluster> {
+        if !(0..self.height() as i32).contains(&y) {
+            return None;
+        }
+        self.clusters.iter().find(|cluster| cluster.x.contains(&x))
+    }
+
+    /// Adds a glyph whose image has the given `(left, top, width, height)` relative to its origin.
+    pub(crate) fn push_glyph(&mut self, glyph: PlacedGlyph, bounds: Option<(i32, i32, u32, u32)>) {
+        if let Some((left, top, width, height)) = bounds &&
+            width > 0 &&
+            height > 0
+        {
+            self.max_x = self.max_x.max(glyph.x + left + width as i32 - 1);
+            self.max_y = self.max_y.max(glyph.y + top + height as i32 - 1);
+        }
+        self.placements.push(Placement::Glyph(glyph));
+    }
+
+    pub(crate) fn push_emoji(&mut self, emoji: PlacedEmoji) {
+        if emoji.size > 0 {
+            self.max_x = self.max_x.max(emoji.x + emoji.size as i32 - 1);
+            self.max_y = self.max_y.max(emoji.y + emoji.size as i32 - 1);
+        }
+        self.placements.push(Placement::Emoji(emoji));
+    }
+
+    /// Adds a cluster, glyphs of the same cluster are merged into one.
+    pub(crate) fn push_cluster(&mut self, segment: usize, bytes: Range<usize>, x: Range<i32>) {
+        if let Some(last) = self.clusters.last_mut() &&
+            last.segment == segment &&
+            last.bytes == bytes
+        {
+            last.x = last.x.start.min(x.start)..last.x.end.max(x.end);
+            return;
+        }
+        self.clusters.push(Cluster { segment, bytes, x });
+    }
+}
+
+impl DrawingContext {
+    /// Draws a prepared [`Layout`] like [`draw`](DrawingContext::draw) draws segments.
+    ///
+    /// Only the color of the context is applied, the layout is not shaped again.
+    /// Nothing is drawn if the layout is stale, see [`Layout`].
+    pub fn draw_layout(&mut self, layout: &Layout, mut f: impl FnMut((i32, i32), [u8; 4])) {
+        if self.is_stale(layout) {
+            return;
+        }
+        for placement in &layout.placements {
+            match *placement {
+                Placement::Glyph(glyph) => self.draw_glyph(glyph, &mut f),
+                Placement::Emoji(emoji) => _ = self.draw_emoji(emoji, &mut f),
+            }
+        }
+    }
+
+    /// Like [`draw_layout`](DrawingContext::draw_layout), but stops at the first emoji that
+    /// cannot be drawn instead of skipping it and fails with [`Error::StaleLayout`] for a stale
+    /// layout.
+    pub fn try_draw_layout(
+        &mut self,
+        layout: &Layout,
+        mut f: impl FnMut((i32, i32), [u8; 4]),
+    ) -> Result<(), Error> {
+        if self.is_stale(layout) {
+            return Err(Error::StaleLayout);
+        }
+        for placement in &layout.placements {
+            match *placement {
+                Placement::Glyph(glyph) => self.draw_glyph(glyph, &mut f),
+                Placement::Emoji(emoji) => self.draw_emoji(emoji, &mut f)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders a prepared [`Layout`] onto a new [`Canvas`] that fits it exactly.
+    pub fn render_layout(&mut self, layout: &Layout) -> Canvas {
+        let (width, height) = layout.dimensions();
+        let mut canvas = Canvas::new(width, height);
+        self.draw_layout(layout, |pos, rgba| canvas.blend(pos, rgba));
+        canvas
+    }
+
+    /// Returns whether the glyphs of the layout refer to the faces of another database.
+    ///
+    /// Emojis don't depend on the fonts, so layouts without glyphs are never stale.
+    pub(crate) fn is_stale(&self, layout: &Layout) -> bool {
+        layout.generation != self.font_database.generation() &&
+            layout
+                .placements
+                .iter()
+                .any(|placement| matches!(placement, Placement::Glyph(_)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FontOrder, Segments};
+
+    #[test]
+    fn layout_is_reused_without_shaping() {
+        let mut ctx = DrawingContext::new();
+        ctx.font_size(24.0);
+        let segments = Segments::new("Hi 🦆");
+        let layout = ctx.layout(&segments);
+        assert_eq!(layout.dimensions(), ctx.dimensions(&segments));
+
+        let mut drawn = Vec::new();
+        ctx.draw(&segments, |pos, rgba| drawn.push((pos, rgba)));
+        let lookups = |ctx: &DrawingContext| {
+            let stats = ctx.cache_stats().capital_info;
+            stats.hits + stats.misses
+        };
+        let before = lookups(&ctx);
+        let mut from_layout = Vec::new();
+        ctx.draw_layout(&layout, |pos, rgba| from_layout.push((pos, rgba)));
+        assert_eq!(drawn, from_layout);
+        assert_eq!(lookups(&ctx), before);
+
+        ctx.rgb(255, 0, 0);
+        let mut recolored = Vec::new();
+        ctx.draw_layout(&layout, |pos, rgba| recolored.push((pos, rgba)));
+        assert_eq!(recolored.len(), drawn.len());
+        assert_ne!(recolored, drawn);
+        assert_eq!(ctx.render_layout(&layout), ctx.render(&segments));
+
+        ctx.font_order(FontOrder::SerifFirst);
+        assert!(matches!(
+            ctx.try_draw_layout(&layout, |_, _| ()),
+            Err(Error::StaleLayout)
+        ));
+        let mut stale = Vec::new();
+        ctx.draw_layout(&layout, |pos, rgba| stale.push((pos, rgba)));
+        assert!(stale.is_empty());
+        let emoji_only = ctx.layout(&Segments::new("🦆"));
+        ctx.font_order(FontOrder::SansFirst);
+        assert!(ctx.try_draw_layout(&emoji_only, |_, _| ()).is_ok());
+    }
+
+    #[test]
+    fn hit_test_finds_clusters() {
+        let mut ctx = DrawingContext::new();
+        ctx.font_size(24.0);
+        let layout = ctx.layout(&Segments::new("Hi 🦆"));
+        let (width, height) = layout.dimensions();
+
+        let first = layout.hit_test(0, 0).unwrap();
+        assert_eq!((first.segment, first.bytes.clone()), (0, 0..1));
+        let last = layout
+            .hit_test(width as i32 - 1, height as i32 - 1)
+            .unwrap();
+        assert_eq!((last.segment, last.bytes.clone()), (1, 0..4));
+        assert_eq!(layout.clusters().len(), 4);
+
+        assert_eq!(layout.hit_test(0, height as i32), None);
+        assert_eq!(layout.hit_test(width as i32 + 10, 0), None);
+    }
+}
@@ -9,11 +9,38 @@
 //! All assets are included in the binary, which increases its size (~35MB in release builds), but
 //! ensures the renderer works in any environment, even minimal ones like Docker scratch images or 
 //! embedded systems without font support.
+//! The fonts and emojis require attribution, their copyright notices and license texts are
+//! available via [`licenses`].
 //!
 //! # Rendering
 //! Rendering is decoupled from any specific imaging or graphics backend. 
 //! Instead, [`DrawingContext`] offers a [`draw`](DrawingContext::draw) method that invokes a 
 //! user-provided callback for each pixel, giving its `(x, y)` coordinate and color. 
+//! To measure, hit test or draw the same text repeatedly, shape it once into a [`Layout`] via
+//! [`layout`](DrawingContext::layout).
+//!
+//! Drawing never fails, characters without a glyph and emojis that cannot be drawn are skipped.
+//! Use the `try_` variants like [`try_draw`](DrawingContext::try_draw) or
+//! [`try_build`](DrawingContextBuilder::try_build) to get an [`Error`] instead.
+//!
+//! For vector output, [`render_svg`](DrawingContext::render_svg) produces an SVG document with 
+//! the same layout, while [`outlines`](DrawingContext::outlines) exposes the raw glyph paths and 
+//! emoji trees for custom vector pipelines.
+//! If you just need the pixels, [`render`](DrawingContext::render) returns them as a [`Canvas`],
+//! which can also be printed straight to a terminal as ANSI half-blocks, sixel or kitty graphics.
+//!
+//! # Features
+//! - `embedded-graphics`: Adds [`DrawingContext::draw_target`] to render directly onto any
+//!   [`embedded-graphics`](https://crates.io/crates/embedded-graphics) `DrawTarget`.
+//! - `pdf`: Adds [`DrawingContext::render_pdf`] to write the segments as vector graphics onto a 
+//!   PDF page.
+//! - `rayon`: Adds [`DrawingContext::render_batch`] to render many segments in parallel.
+//! - `wasm`: Exports a [`wasm-bindgen`](https://crates.io/crates/wasm-bindgen) API for 
+//!   `wasm32-unknown-unknown` builds, see [`WasmDrawingContext`].
+//!   Font files and system fonts cannot be loaded there, only the bundled and pre-loaded fonts
+//!   are available.
+//!   The tests run under node via `cargo test --target wasm32-unknown-unknown --features wasm`
+//!   with `wasm-bindgen-test-runner` installed.
 //!
 //! # Usage
 //! A minimal setup requires:
@@ -36,10 +63,11 @@
 pub mod fonts {
     //! This module contains all the fonts included in `hieroglyph`.
     //!
-    //! It provides static arrays of unhinted OTF files for Google Noto fonts, divided into three 
+    //! It provides static arrays of unhinted OTF files for Google Noto fonts, divided into four 
     //! categories:
     //! - **Sans serif fonts** ([`NOTO_SANS`])
     //! - **Serif fonts** ([`NOTO_SERIF`])
+    //! - **Monospace fonts** ([`NOTO_MONO`])
     //! - **Other fonts** ([`NOTO_REST`])
     //!
     //! The fonts are pulled from the
@@ -47,13 +75,79 @@ pub mod fonts {
     //! and are embedded directly in the binary using [`include_bytes!`]. 
     //! The arrays' lengths and the order of files are determined by the available files and should 
     //! be treated as an implementation detail.
+    //! To pick individual fonts, use the metadata generated alongside, see [`FontInfo`], [`all`]
+    //! and [`by_family`].
+    //!
+    //! # Selecting fonts
+    //!
+    //! By default every font is embedded.
+    //! To embed only some of them, set `HIEROGLYPH_FONTS` to a comma-separated list or
+    //! `HIEROGLYPH_FONT_MANIFEST` to the absolute path of a file with one entry per line, `#`
+    //! starts a comment.
+    //! Both are read at build time, for example from the `[env]` table of `.cargo/config.toml`:
+    //! ```toml
+    //! [env]
+    //! HIEROGLYPH_FONTS = "Noto Sans, Egyptian_Hieroglyphs"
+    //! HIEROGLYPH_FONT_MANIFEST = { value = "fonts.txt", relative = true }
+    //! ```
+    //! An entry is a family like `Noto Sans`, a file name like `NotoSans-Regular.otf` or a script
+    //! like `Egyptian_Hieroglyphs`, which selects every font with the most glyphs in that script.
+    //! The build fails if an entry matches no font, a selection without entries embeds all fonts.
+    //! Emojis are not affected, they are always available.
+    //!
+    //! # Subsetting fonts
+    //!
+    //! The embedded fonts can be reduced to the characters an application needs.
+    //! Set `HIEROGLYPH_SUBSET` to a comma-separated list of code points and ranges like
+    //! `U+0020-007E, U+13000-1342F` or `HIEROGLYPH_SUBSET_TEXT` to the absolute path of a text
+    //! file whose characters are kept, both are combined if set.
+    //! Characters outside of the subset are removed from the fonts, [`SUBSET`] lists the kept
+    //! ones.
+    //! Glyphs used by shaping, like ligatures and contextual forms, are kept if a substitution
+    //! can produce them from the glyphs of kept characters.
+    //! Variation sequences of the kept characters are kept as well.
+    //! A font that cannot be subset is embedded completely and the build emits a warning.
     //! 
     //! This module is generated at build time and is found in the `OUT_DIR`.
     include!(concat!(env!("OUT_DIR"), "/fonts.rs"));
 }
 
+#[cfg(feature = "rayon")]
+mod batch;
+mod cache;
+mod canvas;
+mod coverage;
+mod database;
 mod draw;
+#[cfg(feature = "embedded-graphics")]
+mod embedded;
+mod error;
+mod family;
+mod layout;
+mod license;
+mod outline;
+#[cfg(feature = "pdf")]
+mod pdf;
+mod report;
 mod segments;
+mod svg;
+mod terminal;
+#[cfg(feature = "wasm")]
+mod wasm;
 
+pub use cache::*;
+pub use canvas::*;
+pub use database::*;
 pub use draw::*;
+#[cfg(feature = "embedded-graphics")]
+pub use embedded::*;
+pub use error::*;
+pub use layout::*;
+pub use license::*;
+pub use outline::*;
+pub use report::*;
+pub use resvg::usvg;
 pub use segments::*;
+pub use unicode_script::Script;
+#[cfg(feature = "wasm")]
+pub use wasm::*;
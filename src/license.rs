@@ -0,0 +1,75 @@
+use crate::fonts;
+
+/// License of an asset bundled into the crate, see [`licenses`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct License {
+    /// What the license covers, a font family like `"Noto Sans"` or `"Twemoji"`.
+    pub name: &'static str,
+
+    /// SPDX identifier of the license, `"unknown"` if it could not be determined.
+    pub spdx: &'static str,
+
+    /// Copyright notice of the asset, empty if it has none.
+    pub copyright: &'static str,
+
+    /// Text of the license or, if the full text is not bundled, a notice pointing to it.
+    pub text: &'static str,
+}
+
+/// The Twemoji graphics drawn for emojis.
+const TWEMOJI: License = License {
+    name: "Twemoji",
+    spdx: "CC-BY-4.0",
+    copyright: "Copyright 2019 Twitter, Inc and other contributors",
+    text: "Graphics licensed under CC-BY 4.0: https://creativecommons.org/licenses/by/4.0/\n\
+           Source: https://github.com/jdecked/twemoji",
+};
+
+/// Returns the licenses of all assets bundled into the binary, one per embedded font and one for
+/// the emojis.
+///
+/// The Noto fonts are licensed under the SIL Open Font License and the Twemoji graphics under
+/// CC-BY 4.0, both require attribution when they are redistributed, e.g. in an "About" dialog:
+/// ```rust
+/// # use hieroglyph::*;
+/// #
+/// for license in licenses() {
+///     println!("{} ({}): {}", license.name, license.spdx, license.copyright);
+/// }
+/// ```
+pub fn licenses() -> impl Iterator<Item = License> {
+    fonts::all()
+        .iter()
+        .map(|font| License {
+            name: font.family,
+            spdx: font.license,
+            copyright: font.copyright,
+            text: font.license_text,
+        })
+        .chain([TWEMOJI])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn licenses_cover_bundled_assets() {
+        let licenses: Vec<_> = licenses().collect();
+        assert_eq!(licenses.len(), fonts::all().len() + 1);
+        assert!(licenses.contains(&TWEMOJI));
+        for font in fonts::all() {
+            assert!(licenses.iter().any(|license| license.name == font.family));
+        }
+        assert!(licenses.iter().all(|license| !license.text.is_empty()));
+        for license in licenses.iter().filter(|license| license.spdx == "OFL-1.1") {
+            // archives without license files fall back to the description in the font
+            assert!(
+                license.text.contains("SIL OPEN FONT LICENSE") ||
+                    license.text.contains("Open Font License"),
+                "{}",
+                license.name
+            );
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use std::sync::Arc;
+
+use cosmic_text::Command;
+use resvg::usvg::{Transform, Tree};
+
+use crate::draw::Placement;
+use crate::{DrawingContext, EmojiSegment, Layout, Segments};
+
+/// A single drawing command of a glyph outline.
+///
+/// All coordinates are absolute and in layout space, the same space
+/// [`draw`](DrawingContext::draw) reports its pixels in: the origin is at the top-left and the
+/// y-axis points downwards.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PathCommand {
+    /// Starts a new contour at `(x, y)`.
+    MoveTo(f32, f32),
+
+    /// Draws a straight line to `(x, y)`.
+    LineTo(f32, f32),
+
+    /// Draws a quadratic Bézier curve with the control point `(x1, y1)` to `(x, y)`.
+    QuadTo(f32, f32, f32, f32),
+
+    /// Draws a cubic Bézier curve with the control points `(x1, y1)` and `(x2, y2)` to `(x, y)`.
+    CubicTo(f32, f32, f32, f32, f32, f32),
+
+    /// Closes the current contour.
+    Close,
+}
+
+/// `GlyphOutline` holds the vector outline of a single laid-out glyph.
+///
+/// The outline is taken from the font that was selected for the glyph and scaled to the current
+/// font size.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphOutline {
+    /// The RGBA color the glyph would be filled with.
+    pub color: [u8; 4],
+
+    /// The commands making up the outline, to be filled using the non-zero winding rule.
+    pub commands: Vec<PathCommand>,
+}
+
+/// `EmojiOutline` holds the vector graphic of a single laid-out emoji.
+///
+/// The [`Tree`] is the same parsed SVG [`DrawingContext::tree`] returns, the [`Transform`] maps
+/// it from its own coordinate space into layout space.
+#[derive(Debug, Clone)]
+pub struct EmojiOutline {
+    /// The emoji this outline was created for.
+    pub segment: EmojiSegment,
+
+    /// The parsed SVG tree of the emoji.
+    pub tree: Arc<Tree>,
+
+    /// The transform placing the tree in layout space.
+    pub transform: Transform,
+}
+
+/// Outline represents either a glyph or an emoji in vector form.
+///
+/// The methods [`as_glyph`](Outline::as_glyph) and [`as_emoji`](Outline::as_emoji) let you
+/// easily get the inner value as an [`Option`].
+#[derive(Debug, Clone)]
+pub enum Outline {
+    Glyph(GlyphOutline),
+    Emoji(EmojiOutline),
+}
+
+impl Outline {
+    /// Returns the inner [`GlyphOutline`] if this is a glyph outline, or [`None`] otherwise.
+    pub fn as_glyph(&self) -> Option<&GlyphOutline> {
+        match self {
+            Outline::Glyph(glyph_outline) => Some(glyph_outline),
+            Outline::Emoji(_) => None,
+        }
+    }
+
+    /// Returns the inner [`EmojiOutline`] if this is an emoji outline, or [`None`] otherwise.
+    pub fn as_emoji(&self) -> Option<&EmojiOutline> {
+        match self {
+            Outline::Glyph(_) => None,
+            Outline::Emoji(emoji_outline) => Some(emoji_outline),
+        }
+    }
+}
+
+impl DrawingContext {
+    /// Lays out the provided segments and returns them as vector outlines.
+    ///
+    /// This method uses the current settings (font size, color, caches, etc.) and places every
+    /// glyph and emoji exactly where [`draw`](DrawingContext::draw) would put its pixels.
+    /// Instead of rasterizing, each glyph is returned as a [`GlyphOutline`] made of
+    /// [`PathCommand`]s and each emoji as an [`EmojiOutline`] holding its parsed SVG tree.
+    ///
+    /// Glyphs without an outline, like spaces, are skipped.
+    /// The outlines are returned in drawing order.
+    pub fn outlines(&mut self, segments: &Segments) -> Vec<Outline> {
+        let layout = self.layout(segments);
+        self.layout_outlines(&layout)
+    }
+
+    /// Returns the outlines of a prepared [`Layout`] like [`outlines`](DrawingContext::outlines)
+    /// does for segments.
+    ///
+    /// Only the color of the context is applied, the layout is not shaped again.
+    /// A stale layout has no outlines, see [`Layout`].
+    pub fn layout_outlines(&mut self, layout: &Layout) -> Vec<Outline> {
+        let mut outlines = Vec::new();
+        if self.is_stale(layout) {
+            return outlines;
+        }
+        for &placement in &layout.placements {
+            match placement {
+                Placement::Glyph(glyph) => {
+                    let color = self.glyph_color(&glyph).as_rgba();
+                    let origin = (
+                        glyph.x as f32 + glyph.cache_key.x_bin.as_float(),
+                        glyph.y as f32 + glyph.cache_key.y_bin.as_float(),
+                    );
+                    let Some(commands) = self.outline_commands(glyph.cache_key) else {
+                        continue;
+                    };
+                    if commands.is_empty() {
+                        continue;
+                    }
+
+                    let commands = path_commands(commands, origin);
+                    outlines.push(Outline::Glyph(GlyphOutline { color, commands }));
+                }
+                Placement::Emoji(emoji) => {
+                    let Ok(tree) = self.shared_tree(emoji.segment) else {
+                        continue;
+                    };
+                    let tree = tree.clone();
+                    let scale = emoji.size as f32 / tree.size().width();
+                    let transform =
+                        Transform::from_row(scale, 0.0, 0.0, scale, emoji.x as f32, emoji.y as f32);
+                    outlines.push(Outline::Emoji(EmojiOutline {
+                        segment: emoji.segment,
+                        tree,
+                        transform,
+                    }));
+                }
+            }
+        }
+
+        outlines
+    }
+}
+
+/// Converts outline commands into [`PathCommand`]s.
+///
+/// Font outlines point upwards, so the y-axis is flipped while moving the outline to `origin`.
+fn path_commands(commands: &[Command], (x, y): (f32, f32)) -> Vec<PathCommand> {
+    commands
+        .iter()
+        .map(|command| match *command {
+            Command::MoveTo(p) => PathCommand::MoveTo(x + p.x, y - p.y),
+            Command::LineTo(p) => PathCommand::LineTo(x + p.x, y - p.y),
+            Command::QuadTo(c, p) => PathCommand::QuadTo(x + c.x, y - c.y, x + p.x, y - p.y),
+            Command::CurveTo(c1, c2, p) => {
+                PathCommand::CubicTo(x + c1.x, y - c1.y, x + c2.x, y - c2.y, x + p.x, y - p.y)
+            }
+            Command::Close => PathCommand::Close,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outlines_stay_within_dimensions() {
+        let mut ctx = DrawingContext::new();
+        ctx.font_size(24.0);
+        ctx.rgba(10, 20, 30, 40);
+
+        let segments = Segments::new("H 🦆");
+        let (width, height) = ctx.dimensions(&segments);
+        let outlines = ctx.outlines(&segments);
+        assert_eq!(outlines.len(), 2, "the space has no outline");
+
+        let glyph = outlines[0].as_glyph().unwrap();
+        assert_eq!(glyph.color, [10, 20, 30, 40]);
+        assert!(matches!(
+            glyph.commands.first(),
+            Some(PathCommand::MoveTo(..))
+        ));
+        assert!(glyph.commands.contains(&PathCommand::Close));
+        for command in &glyph.commands {
+            let (x, y) = match *command {
+                PathCommand::MoveTo(x, y) | PathCommand::LineTo(x, y) => (x, y),
+                PathCommand::QuadTo(.., x, y) | PathCommand::CubicTo(.., x, y) => (x, y),
+                PathCommand::Close => continue,
+            };
+            assert!(
+                (-1.0..=width as f32 + 1.0).contains(&x),
+                "x = {x} out of bounds"
+            );
+            assert!(
+                (-1.0..=height as f32 + 1.0).contains(&y),
+                "y = {y} out of bounds"
+            );
+        }
+
+        let emoji = outlines[1].as_emoji().unwrap();
+        let (capital_height, _) = ctx.capital_info();
+        let size = emoji.tree.size().to_rect(0.0, 0.0).unwrap();
+        let placed = size.transform(emoji.transform).unwrap();
+        assert_eq!(placed.width().round(), capital_height as f32);
+        assert!(placed.right() <= width as f32 + 1.0);
+    }
+}
@@ -0,0 +1,419 @@
+use std::collections::BTreeSet;
+
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use pdf_writer::types::{LineCapStyle, LineJoinStyle};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref};
+use resvg::tiny_skia::{PathSegment, Pixmap};
+use resvg::usvg::{
+    self, FillRule, Group, LineCap, LineJoin, Node, Paint, PaintOrder, Transform, Tree,
+};
+
+use crate::{DrawingContext, Layout, Outline, PathCommand, Segments};
+
+/// Emojis embedded as images are rasterized at this multiple of their size on the page, so they
+/// stay sharp when zoomed in or printed.
+const RASTER_SCALE: f32 = 4.0;
+
+impl DrawingContext {
+    /// Renders the provided segments onto a single page PDF document.
+    ///
+    /// This method uses the current settings (font size, color, caches, etc.) and the layout of
+    /// [`outlines`](DrawingContext::outlines).
+    /// The page has the size returned by [`dimensions`](DrawingContext::dimensions), with one pixel
+    /// mapping to one PDF point.
+    ///
+    /// Text glyphs are written as vector outlines, so no fonts need to be embedded and the
+    /// document looks the same in every viewer.
+    /// Emojis are converted from their SVG trees into PDF paths with solid fills and strokes.
+    /// Emojis using gradients, patterns, clip paths, masks, filters, embedded images, text,
+    /// strokes painted below their fill or semi-transparent groups cannot be expressed that way,
+    /// they are embedded as compressed images rasterized at four times their size instead.
+    pub fn render_pdf(&mut self, segments: &Segments) -> Vec<u8> {
+        let layout = self.layout(segments);
+        self.render_layout_pdf(&layout)
+    }
+
+    /// Renders a prepared [`Layout`] onto a single page PDF document, see
+    /// [`render_pdf`](DrawingContext::render_pdf).
+    pub fn render_layout_pdf(&mut self, layout: &Layout) -> Vec<u8> {
+        let (width, height) = layout.dimensions();
+        let mut content = Content::new();
+        let mut alphas = BTreeSet::new();
+        let mut images = Vec::new();
+
+        // flip the y-axis, so that we can draw in layout space
+        content.transform([1.0, 0.0, 0.0, -1.0, 0.0, height as f32]);
+        for outline in self.layout_outlines(layout) {
+            match outline {
+                Outline::Glyph(glyph) => {
+                    let [r, g, b, a] = glyph.color;
+                    set_alpha(&mut content, &mut alphas, a);
+                    content.set_fill_rgb(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+                    write_commands(&mut content, &glyph.commands);
+                    content.fill_nonzero();
+                }
+                Outline::Emoji(emoji) => {
+                    content.save_state();
+                    content.transform(matrix(emoji.transform));
+                    if needs_raster(&emoji.tree) {
+                        let size = emoji.tree.size();
+                        let scale = emoji.transform.sx.abs().max(emoji.transform.sy.abs());
+                        // the same emoji at the same size is embedded only once
+                        let key = (emoji.segment.emoji(), scale.to_bits());
+                        let index = match images.iter().position(|(other, _)| *other == key) {
+                            Some(index) => Some(index),
+                            None => rasterize(&emoji.tree, scale * RASTER_SCALE).map(|pixmap| {
+                                images.push((key, pixmap));
+                                images.len() - 1
+                            }),
+                        };
+                        if let Some(index) = index {
+                            // images fill the unit square with their first row at the top
+                            let [w, h] = [size.width(), size.height()];
+                            content.transform([w, 0.0, 0.0, -h, 0.0, h]);
+                            content.x_object(Name(image_name(index).as_bytes()));
+                        }
+                    } else {
+                        write_group(&mut content, &mut alphas, emoji.tree.root());
+                    }
+                    content.restore_state();
+                }
+            }
+        }
+
+        let catalog_id = Ref::new(1);
+        let page_tree_id = Ref::new(2);
+        let page_id = Ref::new(3);
+        let content_id = Ref::new(4);
+        let alpha_ids = (5..).map(Ref::new);
+        // every image needs a second object for its alpha mask
+        let image_ids = (5 + alphas.len() as i32..).step_by(2).map(Ref::new);
+
+        let mut pdf = Pdf::new();
+        pdf.catalog(catalog_id).pages(page_tree_id);
+        pdf.pages(page_tree_id).kids([page_id]).count(1);
+
+        let mut page = pdf.page(page_id);
+        page.parent(page_tree_id)
+            .media_box(Rect::new(0.0, 0.0, width as f32, height as f32))
+            .contents(content_id);
+        let mut resources = page.resources();
+        let mut states = resources.ext_g_states();
+        for (&alpha, id) in alphas.iter().zip(alpha_ids.clone()) {
+            states.pair(Name(alpha_name(alpha).as_bytes()), id);
+        }
+        states.finish();
+        let mut x_objects = resources.x_objects();
+        for (index, id) in (0..images.len()).zip(image_ids.clone()) {
+            x_objects.pair(Name(image_name(index).as_bytes()), id);
+        }
+        x_objects.finish();
+        resources.finish();
+        page.finish();
+
+        for (&alpha, id) in alphas.iter().zip(alpha_ids) {
+            let alpha = alpha as f32 / 255.0;
+            pdf.ext_graphics(id)
+                .non_stroking_alpha(alpha)
+                .stroking_alpha(alpha);
+        }
+        for ((_, pixmap), id) in images.iter().zip(image_ids) {
+            write_image(&mut pdf, id, pixmap);
+        }
+        pdf.stream(content_id, &content.finish());
+
+        pdf.finish()
+    }
+}
+
+/// Returns the name of the graphics state resource setting the given alpha.
+fn alpha_name(alpha: u8) -> String {
+    format!("A{alpha}")
+}
+
+/// Returns the name of the image XObject resource with the given index.
+fn image_name(index: usize) -> String {
+    format!("I{index}")
+}
+
+/// Returns `true` if the tree uses anything [`write_group`] cannot convert into PDF operators.
+///
+/// The opacity of a group applies to its content as a whole, overlapping paths would show
+/// through each other if it was applied to every path instead.
+fn needs_raster(tree: &Tree) -> bool {
+    fn unsupported(group: &Group) -> bool {
+        group.opacity().get() < 1.0 ||
+            group.children().iter().any(|node| match node {
+                Node::Group(group) => unsupported(group),
+                Node::Path(path) => path.paint_order() == PaintOrder::StrokeAndFill,
+                Node::Image(_) | Node::Text(_) => true,
+            })
+    }
+
+    !tree.linear_gradients().is_empty() ||
+        !tree.radial_gradients().is_empty() ||
+        !tree.patterns().is_empty() ||
+        !tree.clip_paths().is_empty() ||
+        !tree.masks().is_empty() ||
+        !tree.filters().is_empty() ||
+        unsupported(tree.root())
+}
+
+/// Writes the pixmap as a deflated RGB image with its alpha channel as soft mask in the
+/// following object.
+fn write_image(pdf: &mut Pdf, id: Ref, pixmap: &Pixmap) {
+    /// Compression level of `miniz_oxide`, from 0 to 10.
+    const LEVEL: u8 = 6;
+
+    let mask_id = Ref::new(id.get() + 1);
+    let (rgb, alpha): (Vec<_>, Vec<_>) = pixmap
+        .pixels()
+        .iter()
+        .map(|pixel| {
+            let pixel = pixel.demultiply();
+            ([pixel.red(), pixel.green(), pixel.blue()], pixel.alpha())
+        })
+        .unzip();
+    let (width, height) = (pixmap.width() as i32, pixmap.height() as i32);
+    let rgb = compress_to_vec_zlib(rgb.as_flattened(), LEVEL);
+    let mut image = pdf.image_xobject(id, &rgb);
+    image.filter(Filter::FlateDecode);
+    image.width(width).height(height).bits_per_component(8);
+    image.color_space().device_rgb();
+    image.s_mask(mask_id);
+    image.finish();
+    let alpha = compress_to_vec_zlib(&alpha, LEVEL);
+    let mut mask = pdf.image_xobject(mask_id, &alpha);
+    mask.filter(Filter::FlateDecode);
+    mask.width(width).height(height).bits_per_component(8);
+    mask.color_space().device_gray();
+    mask.finish();
+}
+
+/// Renders the tree at the given scale.
+fn rasterize(tree: &Tree, scale: f32) -> Option<Pixmap> {
+    let size = tree.size().to_int_size().scale_by(scale)?;
+    let mut pixmap = Pixmap::new(size.width(), size.height())?;
+    let transform = Transform::from_scale(
+        size.width() as f32 / tree.size().width(),
+        size.height() as f32 / tree.size().height(),
+    );
+    resvg::render(tree, transform, &mut pixmap.as_mut());
+    Some(pixmap)
+}
+
+/// Sets the fill and stroke alpha and records that its graphics state resource is needed.
+fn set_alpha(content: &mut Content, alphas: &mut BTreeSet<u8>, alpha: u8) {
+    alphas.insert(alpha);
+    content.set_parameters(Name(alpha_name(alpha).as_bytes()));
+}
+
+fn matrix(transform: Transform) -> [f32; 6] {
+    let Transform {
+        sx,
+        ky,
+        kx,
+        sy,
+        tx,
+        ty,
+    } = transform;
+    [sx, ky, kx, sy, tx, ty]
+}
+
+fn write_commands(content: &mut Content, commands: &[PathCommand]) {
+    // PDF has no quadratic curves, they need to be elevated to cubic ones from the current point
+    let mut start = (0.0, 0.0);
+    let mut current = (0.0, 0.0);
+    for command in commands {
+        match *command {
+            PathCommand::MoveTo(x, y) => {
+                content.move_to(x, y);
+                start = (x, y);
+                current = (x, y);
+            }
+            PathCommand::LineTo(x, y) => {
+                content.line_to(x, y);
+                current = (x, y);
+            }
+            PathCommand::QuadTo(x1, y1, x, y) => {
+                let (x0, y0) = current;
+                content.cubic_to(
+                    x0 + 2.0 / 3.0 * (x1 - x0),
+                    y0 + 2.0 / 3.0 * (y1 - y0),
+                    x + 2.0 / 3.0 * (x1 - x),
+                    y + 2.0 / 3.0 * (y1 - y),
+                    x,
+                    y,
+                );
+                current = (x, y);
+            }
+            PathCommand::CubicTo(x1, y1, x2, y2, x, y) => {
+                content.cubic_to(x1, y1, x2, y2, x, y);
+                current = (x, y);
+            }
+            PathCommand::Close => {
+                content.close_path();
+                current = start;
+            }
+        }
+    }
+}
+
+fn write_group(content: &mut Content, alphas: &mut BTreeSet<u8>, group: &Group) {
+    for node in group.children() {
+        match node {
+            Node::Group(group) => {
+                content.save_state();
+                content.transform(matrix(group.transform()));
+                write_group(content, alphas, group);
+                content.restore_state();
+            }
+            Node::Path(path) => write_path(content, alphas, path),
+            // rasterized instead, see `needs_raster`, as are groups with an opacity
+            Node::Image(_) | Node::Text(_) => (),
+        }
+    }
+}
+
+fn write_path(content: &mut Content, alphas: &mut BTreeSet<u8>, path: &usvg::Path) {
+    if !path.is_visible() {
+        return;
+    }
+
+    let commands: Vec<_> = path
+        .data()
+        .segments()
+        .map(|segment| match segment {
+            PathSegment::MoveTo(p) => PathCommand::MoveTo(p.x, p.y),
+            PathSegment::LineTo(p) => PathCommand::LineTo(p.x, p.y),
+            PathSegment::QuadTo(p1, p) => PathCommand::QuadTo(p1.x, p1.y, p.x, p.y),
+            PathSegment::CubicTo(p1, p2, p) => {
+                PathCommand::CubicTo(p1.x, p1.y, p2.x, p2.y, p.x, p.y)
+            }
+            PathSegment::Close => PathCommand::Close,
+        })
+        .collect();
+
+    if let Some(fill) = path.fill() {
+        let rgb = paint_color(fill.paint());
+        set_alpha(content, alphas, to_u8(fill.opacity().get()));
+        content.set_fill_rgb(rgb[0], rgb[1], rgb[2]);
+        write_commands(content, &commands);
+        match fill.rule() {
+            FillRule::NonZero => content.fill_nonzero(),
+            FillRule::EvenOdd => content.fill_even_odd(),
+        };
+    }
+
+    if let Some(stroke) = path.stroke() {
+        let rgb = paint_color(stroke.paint());
+        set_alpha(content, alphas, to_u8(stroke.opacity().get()));
+        content.set_stroke_rgb(rgb[0], rgb[1], rgb[2]);
+        content.set_line_width(stroke.width().get());
+        content.set_line_cap(match stroke.linecap() {
+            LineCap::Butt => LineCapStyle::ButtCap,
+            LineCap::Round => LineCapStyle::RoundCap,
+            LineCap::Square => LineCapStyle::ProjectingSquareCap,
+        });
+        content.set_line_join(match stroke.linejoin() {
+            LineJoin::Miter | LineJoin::MiterClip => LineJoinStyle::MiterJoin,
+            LineJoin::Round => LineJoinStyle::RoundJoin,
+            LineJoin::Bevel => LineJoinStyle::BevelJoin,
+        });
+        content.set_miter_limit(stroke.miterlimit().get());
+        if let Some(dashes) = stroke.dasharray() {
+            content.set_dash_pattern(dashes.iter().copied(), stroke.dashoffset());
+        }
+        write_commands(content, &commands);
+        content.stroke();
+    }
+}
+
+/// Returns the color of a paint as normalized RGB values.
+///
+/// Trees with other paints are rasterized, see [`needs_raster`].
+fn paint_color(paint: &Paint) -> [f32; 3] {
+    match paint {
+        Paint::Color(color) => [color.red, color.green, color.blue].map(|c| c as f32 / 255.0),
+        Paint::LinearGradient(_) | Paint::RadialGradient(_) | Paint::Pattern(_) => [0.0; 3],
+    }
+}
+
+fn to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pdf_contains_page() {
+        let mut ctx = DrawingContext::new();
+        ctx.font_size(24.0);
+        ctx.rgba(0, 0, 0, 128);
+
+        let segments = Segments::new("H 🦆");
+        let (width, height) = ctx.dimensions(&segments);
+        let pdf = ctx.render_pdf(&segments);
+        let pdf = String::from_utf8_lossy(&pdf);
+
+        assert!(pdf.starts_with("%PDF-"));
+        assert!(pdf.trim_end().ends_with("%%EOF"));
+        assert!(pdf.contains(&format!("/MediaBox [0 0 {width} {height}]")));
+        assert!(pdf.contains("/A128"), "glyph alpha is registered");
+        assert!(pdf.contains("/A255"), "emoji alpha is registered");
+    }
+
+    #[test]
+    fn unsupported_emojis_are_rasterized() {
+        let parse = |svg: &str| Tree::from_str(svg, &usvg::Options::default()).unwrap();
+        let solid = parse(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 36 36"><circle fill="#FFCC4D" cx="18" cy="18" r="18"/></svg>"##,
+        );
+        let gradient = parse(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 36 36"><linearGradient id="g"><stop offset="0" stop-color="#F00"/><stop offset="1" stop-color="#00F"/></linearGradient><circle fill="url(#g)" cx="18" cy="18" r="18"/></svg>"##,
+        );
+        let translucent = parse(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 36 36"><g opacity="0.5"><circle fill="#FFCC4D" cx="14" cy="18" r="14"/><circle fill="#664500" cx="22" cy="18" r="14"/></g></svg>"##,
+        );
+        assert!(!needs_raster(&solid));
+        assert!(needs_raster(&gradient));
+        assert!(needs_raster(&translucent));
+
+        let pixmap = rasterize(&gradient, 2.0).unwrap();
+        assert_eq!((pixmap.width(), pixmap.height()), (72, 72));
+        let center = pixmap.pixel(36, 36).unwrap();
+        assert_eq!(center.alpha(), 255);
+        assert!(
+            center.red() > 0 && center.blue() > 0,
+            "stops are interpolated"
+        );
+    }
+
+    #[test]
+    fn images_are_compressed() {
+        let mut pixmap = Pixmap::new(64, 64).unwrap();
+        pixmap.fill(resvg::tiny_skia::Color::from_rgba8(255, 204, 77, 255));
+        let mut pdf = Pdf::new();
+        write_image(&mut pdf, Ref::new(1), &pixmap);
+        let pdf = pdf.finish();
+
+        assert!(pdf.len() < 64 * 64, "a uniform image compresses well");
+        let pdf = String::from_utf8_lossy(&pdf);
+        assert_eq!(pdf.matches("/Filter /FlateDecode").count(), 2);
+        assert!(pdf.contains("/SMask 2 0 R"));
+    }
+
+    #[test]
+    fn quads_are_elevated() {
+        let mut content = Content::new();
+        write_commands(&mut content, &[
+            PathCommand::MoveTo(0.0, 0.0),
+            PathCommand::QuadTo(3.0, 3.0, 6.0, 0.0),
+            PathCommand::Close,
+        ]);
+        let content = String::from_utf8(content.finish()).unwrap();
+        assert_eq!(content, "0 0 m\n2 2 4 2 6 0 c\nh");
+    }
+}
@@ -0,0 +1,233 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+use cosmic_text::fontdb::{self, Database, FaceInfo, ID};
+use cosmic_text::ttf_parser::Face;
+
+use crate::coverage::{char_ranges, cmap_chars};
+
+/// A font passed via [`pre_fonts`](crate::DrawingContextBuilder::pre_fonts) or loaded from a file
+/// as it was loaded.
+///
+/// Get the reports of all fonts supplied by the user via
+/// [`FontDatabase::user_fonts`](crate::FontDatabase::user_fonts).
+/// The [`Display`](fmt::Display) implementation prints a short summary, one line per face, which
+/// is handy for startup logs:
+/// ```rust
+/// # use hieroglyph::*;
+/// #
+/// # let your_fonts = std::iter::once(fonts::NOTO_SANS[0].to_vec());
+/// let font_database = DrawingContext::configure()
+///     .pre_fonts(your_fonts)
+///     .try_build_font_database()
+///     .expect("fonts are valid");
+/// for font in font_database.user_fonts() {
+///     println!("{font}");
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadedFont {
+    /// Where the font came from.
+    pub origin: FontOrigin,
+
+    /// The faces found in the font, more than one for font collections like `.ttc` files.
+    ///
+    /// Empty if the font could not be parsed.
+    pub faces: Vec<LoadedFace>,
+}
+
+/// A single face of a [`LoadedFont`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadedFace {
+    /// Family names, the first one is the English name if the font has one.
+    pub families: Vec<String>,
+
+    /// The PostScript name of the face.
+    pub post_script_name: String,
+
+    /// The style of the face.
+    pub style: FontStyle,
+
+    /// The weight of the face, from `100` (thin) to `900` (black), `400` being regular.
+    pub weight: u16,
+
+    /// Number of glyphs in the face.
+    pub glyphs: u16,
+
+    coverage: Vec<RangeInclusive<char>>,
+}
+
+/// Where a [`LoadedFont`] came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FontOrigin {
+    /// Position of the font in the [`pre_fonts`](crate::DrawingContextBuilder::pre_fonts)
+    /// iterator.
+    PreFont(usize),
+
+    /// Path of the font file.
+    File(PathBuf),
+}
+
+/// Style of a [`LoadedFace`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FontStyle {
+    Normal,
+    Italic,
+    Oblique,
+}
+
+impl LoadedFont {
+    /// Returns `true` if the font contains at least one readable face.
+    pub fn is_valid(&self) -> bool {
+        !self.faces.is_empty()
+    }
+
+    /// Loads the font into the database and reports what was found.
+    pub(crate) fn load(font_db: &mut Database, origin: FontOrigin, source: fontdb::Source) -> Self {
+        let faces = font_db
+            .load_font_source(source)
+            .into_iter()
+            .filter_map(|id| LoadedFace::new(font_db, id))
+            .collect();
+        Self { origin, faces }
+    }
+}
+
+impl LoadedFace {
+    fn new(font_db: &Database, id: ID) -> Option<Self> {
+        let FaceInfo {
+            families,
+            post_script_name,
+            style,
+            weight,
+            ..
+        } = font_db.face(id)?;
+        let (glyphs, coverage) = font_db.with_face_data(id, |data, index| {
+            let face = Face::parse(data, index).ok()?;
+            Some((face.number_of_glyphs(), char_ranges(&cmap_chars(&face))))
+        })??;
+
+        Some(Self {
+            families: families.iter().map(|(name, _)| name.clone()).collect(),
+            post_script_name: post_script_name.clone(),
+            style: match style {
+                fontdb::Style::Normal => FontStyle::Normal,
+                fontdb::Style::Italic => FontStyle::Italic,
+                fontdb::Style::Oblique => FontStyle::Oblique,
+            },
+            weight: weight.0,
+            glyphs,
+            coverage,
+        })
+    }
+
+    /// Returns the primary family name.
+    pub fn family(&self) -> &str {
+        self.families.first().map_or("", String::as_str)
+    }
+
+    /// Returns the ranges of characters the face has a glyph for, in ascending order.
+    pub fn coverage(&self) -> &[RangeInclusive<char>] {
+        &self.coverage
+    }
+
+    /// Returns the number of characters the face has a glyph for.
+    pub fn char_count(&self) -> usize {
+        char_count(&self.coverage)
+    }
+
+    /// Returns `true` if the face has a glyph for `c`.
+    pub fn covers(&self, c: char) -> bool {
+        ranges_contain(&self.coverage, c)
+    }
+}
+
+/// Counts the characters in ascending, non-overlapping ranges.
+pub(crate) fn char_count(ranges: &[RangeInclusive<char>]) -> usize {
+    ranges
+        .iter()
+        .map(|range| *range.end() as usize - *range.start() as usize + 1)
+        .sum()
+}
+
+/// Returns `true` if one of the ascending, non-overlapping ranges contains `c`.
+pub(crate) fn ranges_contain(ranges: &[RangeInclusive<char>], c: char) -> bool {
+    ranges
+        .binary_search_by(|range| {
+            if *range.end() < c {
+                std::cmp::Ordering::Less
+            } else if *range.start() > c {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
+impl fmt::Display for FontOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontOrigin::PreFont(index) => write!(f, "pre-font {index}"),
+            FontOrigin::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+impl fmt::Display for LoadedFont {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.faces.is_empty() {
+            return write!(f, "{}: no readable face", self.origin);
+        }
+        for (i, face) in self.faces.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{}, face {i}: {} ({:?}, {}), {} glyphs covering {} characters",
+                self.origin,
+                face.family(),
+                face.style,
+                face.weight,
+                face.glyphs,
+                face.char_count(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DrawingContext, fonts};
+
+    #[test]
+    fn pre_fonts_are_reported() {
+        let font_database = DrawingContext::configure()
+            .pre_fonts([fonts::NOTO_SANS[0].to_vec(), b"not a font".to_vec()].into_iter())
+            .build_font_database();
+        let [valid, invalid] = font_database.user_fonts() else {
+            panic!("expected two reports");
+        };
+
+        assert_eq!(valid.origin, FontOrigin::PreFont(0));
+        assert_eq!(invalid.origin, FontOrigin::PreFont(1));
+        assert!(valid.is_valid() && !invalid.is_valid());
+        let face = &valid.faces[0];
+        assert!(!face.family().is_empty());
+        assert!(face.glyphs > 0);
+        assert!(face.covers('A') && !face.covers('\u{E000}'));
+        assert_eq!(
+            face.char_count(),
+            face.coverage()
+                .iter()
+                .flat_map(|range| range.clone())
+                .count()
+        );
+        assert!(valid.to_string().contains(face.family()));
+        assert_eq!(invalid.to_string(), "pre-font 1: no readable face");
+    }
+}
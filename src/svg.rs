@@ -0,0 +1,162 @@
+use resvg::usvg::Transform;
+
+use crate::{DrawingContext, Layout, Outline, PathCommand, Segments};
+
+impl DrawingContext {
+    /// Renders the provided segments into a standalone SVG document.
+    ///
+    /// This method uses the current settings (font size, color, caches, etc.) and lays out the
+    /// [`Segments`] exactly like [`draw`](DrawingContext::draw) does.
+    /// Text glyphs are written as `<path>` elements using the outlines from the font, emojis are
+    /// embedded as groups containing their Twemoji SVG (see [`EmojiSegment::svg`]).
+    ///
+    /// The document size matches the one returned by
+    /// [`dimensions`](DrawingContext::dimensions), so the vector output lines up with the
+    /// rasterized one.
+    ///
+    /// [`EmojiSegment::svg`]: crate::EmojiSegment::svg
+    pub fn render_svg(&mut self, segments: &Segments) -> String {
+        let layout = self.layout(segments);
+        self.render_layout_svg(&layout)
+    }
+
+    /// Renders a prepared [`Layout`] into a standalone SVG document, see
+    /// [`render_svg`](DrawingContext::render_svg).
+    pub fn render_layout_svg(&mut self, layout: &Layout) -> String {
+        let (width, height) = layout.dimensions();
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+        );
+
+        for outline in self.layout_outlines(layout) {
+            match outline {
+                Outline::Glyph(glyph) => {
+                    let [r, g, b, a] = glyph.color;
+                    let opacity = match a {
+                        255 => String::new(),
+                        a => format!(r#" fill-opacity="{}""#, round(a as f32 / 255.0)),
+                    };
+                    let path = path_data(&glyph.commands);
+                    svg.push_str(&format!(
+                        r##"<path fill="#{r:02X}{g:02X}{b:02X}"{opacity} d="{path}"/>"##
+                    ));
+                }
+                Outline::Emoji(emoji) => {
+                    let Transform {
+                        sx,
+                        ky,
+                        kx,
+                        sy,
+                        tx,
+                        ty,
+                    } = emoji.transform;
+                    let matrix = [sx, ky, kx, sy, tx, ty].map(round);
+                    let [a, b, c, d, e, f] = matrix;
+                    svg.push_str(&format!(
+                        r#"<g transform="matrix({a} {b} {c} {d} {e} {f})">{}</g>"#,
+                        svg_body(emoji.segment.svg())
+                    ));
+                }
+            }
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+/// Converts path commands into SVG path data.
+fn path_data(commands: &[PathCommand]) -> String {
+    let mut data = String::new();
+    let mut push = |op: char, points: &[(f32, f32)]| {
+        data.push(op);
+        for (i, (x, y)) in points.iter().enumerate() {
+            if i > 0 {
+                data.push(' ');
+            }
+            data.push_str(&format!("{} {}", round(*x), round(*y)));
+        }
+    };
+
+    for command in commands {
+        match *command {
+            PathCommand::MoveTo(x, y) => push('M', &[(x, y)]),
+            PathCommand::LineTo(x, y) => push('L', &[(x, y)]),
+            PathCommand::QuadTo(x1, y1, x, y) => push('Q', &[(x1, y1), (x, y)]),
+            PathCommand::CubicTo(x1, y1, x2, y2, x, y) => push('C', &[(x1, y1), (x2, y2), (x, y)]),
+            PathCommand::Close => push('Z', &[]),
+        }
+    }
+
+    data
+}
+
+/// Returns the content of the outermost `<svg>` element.
+fn svg_body(svg: &str) -> &str {
+    let body = (|| {
+        let start = svg.find("<svg")?;
+        let start = start + svg[start..].find('>')? + 1;
+        let end = svg.rfind("</svg>")?;
+        svg.get(start..end)
+    })();
+    body.unwrap_or_default()
+}
+
+/// Rounds to two decimal places to keep the output compact.
+fn round(value: f32) -> f32 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use resvg::tiny_skia::Pixmap;
+    use resvg::usvg::{Options, Tree};
+
+    use super::*;
+
+    #[test]
+    fn svg_body_works() {
+        let svg =
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 36 36"><path d="M0 0"/></svg>"#;
+        assert_eq!(svg_body(svg), r#"<path d="M0 0"/>"#);
+        assert_eq!(svg_body("no svg"), "");
+    }
+
+    #[test]
+    fn svg_matches_drawn_output() {
+        let mut ctx = DrawingContext::new();
+        ctx.font_size(32.0);
+
+        let segments = Segments::new("Hi 🦆 there");
+        let svg = ctx.render_svg(&segments);
+        let layout = ctx.layout(&segments);
+        assert_eq!(ctx.render_layout_svg(&layout), svg);
+        let (width, height) = layout.dimensions();
+
+        let tree = Tree::from_str(&svg, &Options::default()).unwrap();
+        assert_eq!(tree.size().width(), width as f32);
+        assert_eq!(tree.size().height(), height as f32);
+
+        let mut pixmap = Pixmap::new(width, height).unwrap();
+        resvg::render(&tree, Transform::identity(), &mut pixmap.as_mut());
+
+        let mut drawn = vec![false; (width * height) as usize];
+        ctx.draw(&segments, |(x, y), [.., a]| {
+            if a > 127 && x >= 0 && y >= 0 {
+                drawn[(y as u32 * width + x as u32) as usize] = true;
+            }
+        });
+        let rendered = pixmap.pixels().iter().map(|pixel| pixel.alpha() > 127);
+
+        let (mut both, mut any) = (0, 0);
+        for (drawn, rendered) in drawn.into_iter().zip(rendered) {
+            both += (drawn && rendered) as u32;
+            any += (drawn || rendered) as u32;
+        }
+        assert!(any > 0);
+        assert!(
+            both as f32 / any as f32 > 0.8,
+            "svg output diverges from drawn output: {both} of {any} pixels overlap"
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::Canvas;
+
+/// Pixels with a lower alpha are treated as fully transparent by the terminal encoders.
+const ALPHA_THRESHOLD: u8 = 128;
+
+/// Maximum payload size of a single kitty graphics escape sequence.
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+impl Canvas {
+    /// Encodes the canvas as ANSI 24-bit color art using half-block characters.
+    ///
+    /// Each character cell shows two vertically stacked pixels, the upper one as the foreground
+    /// color of `▀` and the lower one as the background color.
+    /// Pixels with an alpha below 50% keep the terminal's default colors, every other pixel is
+    /// drawn opaque.
+    /// To get smooth edges, [`fill`](Canvas::fill) the canvas with the terminal's background
+    /// color before rendering onto it.
+    ///
+    /// Every line ends with a reset sequence and a line break.
+    pub fn to_ansi(&self) -> String {
+        #[derive(Copy, Clone, PartialEq)]
+        enum Color {
+            Default,
+            Rgb([u8; 3]),
+        }
+
+        let color = |x, y| match self.pixel(x, y) {
+            Some([r, g, b, a]) if a >= ALPHA_THRESHOLD => Color::Rgb([r, g, b]),
+            _ => Color::Default,
+        };
+
+        let mut out = String::new();
+        for y in (0..self.height()).step_by(2) {
+            let (mut current_fg, mut current_bg) = (Color::Default, Color::Default);
+            for x in 0..self.width() {
+                let (char, fg, bg) = match (color(x, y), color(x, y + 1)) {
+                    (Color::Default, Color::Default) => (' ', current_fg, Color::Default),
+                    (Color::Default, lower) => ('▄', lower, Color::Default),
+                    (upper, lower) => ('▀', upper, lower),
+                };
+
+                if fg != current_fg {
+                    match fg {
+                        Color::Default => out.push_str("\x1b[39m"),
+                        Color::Rgb([r, g, b]) => _ = write!(out, "\x1b[38;2;{r};{g};{b}m"),
+                    }
+                    current_fg = fg;
+                }
+                if bg != current_bg {
+                    match bg {
+                        Color::Default => out.push_str("\x1b[49m"),
+                        Color::Rgb([r, g, b]) => _ = write!(out, "\x1b[48;2;{r};{g};{b}m"),
+                    }
+                    current_bg = bg;
+                }
+                out.push(char);
+            }
+            out.push_str("\x1b[0m\n");
+        }
+
+        out
+    }
+
+    /// Encodes the canvas in the DEC sixel format.
+    ///
+    /// Colors are quantized to a 6x6x6 color cube, so at most 216 color registers are used.
+    /// Pixels with an alpha below 50% are left transparent.
+    pub fn to_sixel(&self) -> String {
+        let quantize = |c: u8| (c as u16 * 5 + 127) / 255;
+        let register = |x, y| match self.pixel(x, y) {
+            Some([r, g, b, a]) if a >= ALPHA_THRESHOLD => {
+                Some(quantize(r) * 36 + quantize(g) * 6 + quantize(b))
+            }
+            _ => None,
+        };
+
+        let mut out = format!("\x1bP0;1q\"1;1;{};{}", self.width(), self.height());
+
+        let mut registers: Vec<u16> = (0..self.height())
+            .flat_map(|y| (0..self.width()).map(move |x| (x, y)))
+            .filter_map(|(x, y)| register(x, y))
+            .collect();
+        registers.sort_unstable();
+        registers.dedup();
+        for register in registers {
+            let [r, g, b] = [register / 36, register / 6 % 6, register % 6].map(|level| level * 20);
+            _ = write!(out, "#{register};2;{r};{g};{b}");
+        }
+
+        for band in (0..self.height()).step_by(6) {
+            // collect the sixel bits of each color register in this band
+            let mut bits: BTreeMap<u16, Vec<u8>> = BTreeMap::new();
+            for dy in 0..6.min(self.height() - band) {
+                for x in 0..self.width() {
+                    if let Some(register) = register(x, band + dy) {
+                        let row = bits
+                            .entry(register)
+                            .or_insert_with(|| vec![0; self.width() as usize]);
+                        row[x as usize] |= 1 << dy;
+                    }
+                }
+            }
+
+            for (register, row) in bits {
+                _ = write!(out, "#{register}");
+                let end = row.iter().rposition(|&bits| bits != 0).map_or(0, |i| i + 1);
+                let mut row = row[..end].iter().peekable();
+                while let Some(&bits) = row.next() {
+                    let mut count = 1;
+                    while row.next_if_eq(&&bits).is_some() {
+                        count += 1;
+                    }
+                    let char = char::from(63 + bits);
+                    match count {
+                        1..=3 => (0..count).for_each(|_| out.push(char)),
+                        _ => _ = write!(out, "!{count}{char}"),
+                    }
+                }
+                out.push('$');
+            }
+            out.push('-');
+        }
+
+        out.push_str("\x1b\\");
+        out
+    }
+
+    /// Encodes the canvas using the kitty terminal graphics protocol.
+    ///
+    /// The image is transmitted as raw RGBA data and displayed at the cursor position.
+    /// Large images are split into multiple escape sequences as required by the protocol.
+    pub fn to_kitty(&self) -> String {
+        let data = base64(self.as_bytes());
+        let chunks: Vec<&str> = match data.is_empty() {
+            true => vec![""],
+            false => data
+                .as_bytes()
+                .chunks(KITTY_CHUNK_SIZE)
+                .map(|chunk| std::str::from_utf8(chunk).expect("base64 is ascii"))
+                .collect(),
+        };
+
+        let mut out = String::new();
+        let last = chunks.len() - 1;
+        for (i, chunk) in chunks.into_iter().enumerate() {
+            out.push_str("\x1b_G");
+            if i == 0 {
+                _ = write!(out, "a=T,f=32,s={},v={}", self.width(), self.height());
+                if last > 0 {
+                    out.push_str(",m=1");
+                }
+            } else {
+                _ = write!(out, "m={}", (i != last) as u8);
+            }
+            _ = write!(out, ";{chunk}\x1b\\");
+        }
+
+        out
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &byte)| n | (byte as u32) << (16 - i * 8));
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => out.push(ALPHABET[(n >> (18 - i * 6)) as usize & 0x3F] as char),
+                false => out.push('='),
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canvas() -> Canvas {
+        let mut canvas = Canvas::new(3, 3);
+        let red = [255, 0, 0, 255];
+        let green = [0, 255, 0, 255];
+        let blue = [0, 0, 255, 255];
+        let white = [255, 255, 255, 255];
+        for (pos, rgba) in [
+            ((0, 0), red),
+            ((2, 0), blue),
+            ((0, 1), red),
+            ((1, 1), green),
+            ((0, 2), white),
+            ((1, 2), [255, 255, 255, 100]),
+        ] {
+            canvas.blend(pos, rgba);
+        }
+        canvas
+    }
+
+    #[test]
+    fn base64_works() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn ansi_matches_fixture() {
+        assert_eq!(canvas().to_ansi(), include_str!("fixtures/canvas.ansi"));
+    }
+
+    #[test]
+    fn sixel_matches_fixture() {
+        assert_eq!(canvas().to_sixel(), include_str!("fixtures/canvas.sixel"));
+    }
+
+    #[test]
+    fn kitty_matches_fixture() {
+        assert_eq!(canvas().to_kitty(), include_str!("fixtures/canvas.kitty"));
+
+        let large = Canvas::new(64, 64).to_kitty();
+        let sequences: Vec<&str> = large.split_inclusive("\x1b\\").collect();
+        assert_eq!(sequences.len(), 6);
+        assert!(sequences[0].starts_with("\x1b_Ga=T,f=32,s=64,v=64,m=1;"));
+        assert!(sequences[1].starts_with("\x1b_Gm=1;"));
+        assert!(sequences[5].starts_with("\x1b_Gm=0;"));
+    }
+}
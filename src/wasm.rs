@@ -0,0 +1,125 @@
+use wasm_bindgen::Clamped;
+use wasm_bindgen::prelude::*;
+
+use crate::{DrawingContext, FontOrder, Segments};
+
+/// JavaScript counterpart of [`FontOrder`], exported as `FontOrder`.
+#[wasm_bindgen(js_name = FontOrder)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WasmFontOrder {
+    SansFirst,
+    SerifFirst,
+    MonoFirst,
+}
+
+/// JavaScript wrapper around [`DrawingContext`], exported as `DrawingContext`.
+///
+/// Methods take plain strings, the [`Segments`] are created internally.
+///
+/// ```js
+/// import init, { DrawingContext, FontOrder } from "./pkg/hieroglyph.js";
+///
+/// await init();
+/// const ctx = new DrawingContext(FontOrder.SansFirst, "en");
+/// ctx.fontSize(32);
+/// const image = ctx.render("Hello 🌍 𓂀");
+/// const imageData = new ImageData(image.data, image.width, image.height);
+/// canvas.getContext("2d").putImageData(imageData, 0, 0);
+/// ```
+#[wasm_bindgen(js_name = DrawingContext)]
+#[derive(Debug)]
+pub struct WasmDrawingContext(DrawingContext);
+
+/// A rendered image, exported as `RenderedImage`.
+///
+/// [`data`](WasmImage::data) is a `Uint8ClampedArray` with non-premultiplied RGBA pixels, just like
+/// `ImageData` expects them.
+#[wasm_bindgen(js_name = RenderedImage)]
+#[derive(Debug, Clone)]
+pub struct WasmImage {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+#[wasm_bindgen(js_class = DrawingContext)]
+impl WasmDrawingContext {
+    /// Creates a new drawing context, both arguments are optional.
+    #[wasm_bindgen(constructor)]
+    pub fn new(font_order: Option<WasmFontOrder>, locale: Option<String>) -> Self {
+        let font_order = match font_order.unwrap_or(WasmFontOrder::SansFirst) {
+            WasmFontOrder::SansFirst => FontOrder::SansFirst,
+            WasmFontOrder::SerifFirst => FontOrder::SerifFirst,
+            WasmFontOrder::MonoFirst => FontOrder::MonoFirst,
+        };
+        let mut builder = DrawingContext::configure().font_order(font_order);
+        if let Some(locale) = locale {
+            builder = builder.locale(locale);
+        }
+        Self(builder.build())
+    }
+
+    /// See [`DrawingContext::font_size`].
+    #[wasm_bindgen(js_name = fontSize)]
+    pub fn font_size(&mut self, font_size: f32) {
+        self.0.font_size(font_size);
+    }
+
+    /// See [`DrawingContext::rgba`].
+    pub fn rgba(&mut self, r: u8, g: u8, b: u8, a: u8) {
+        self.0.rgba(r, g, b, a);
+    }
+
+    /// See [`DrawingContext::width`].
+    pub fn width(&mut self, text: &str) -> u32 {
+        self.0.width(&Segments::new(text))
+    }
+
+    /// Renders the text onto a transparent image that fits it exactly.
+    pub fn render(&mut self, text: &str) -> WasmImage {
+        let canvas = self.0.render(&Segments::new(text));
+        WasmImage {
+            width: canvas.width(),
+            height: canvas.height(),
+            data: canvas.into_bytes(),
+        }
+    }
+}
+
+#[wasm_bindgen(js_class = RenderedImage)]
+impl WasmImage {
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns a copy of the pixel data.
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> Clamped<Vec<u8>> {
+        Clamped(self.data.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test::wasm_bindgen_test)]
+    fn render_fills_image_data() {
+        let mut ctx = WasmDrawingContext::new(Some(WasmFontOrder::SerifFirst), None);
+        ctx.font_size(20.0);
+        ctx.rgba(0, 0, 0, 255);
+
+        let image = ctx.render("Hi 🦆");
+        assert_eq!(image.width(), ctx.width("Hi 🦆"));
+        let Clamped(data) = image.data();
+        assert_eq!(data.len(), (image.width() * image.height() * 4) as usize);
+        assert!(data.chunks_exact(4).any(|pixel| pixel[3] > 0));
+    }
+}
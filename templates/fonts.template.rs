@@ -1,5 +1,9 @@
 // @generated
 
+use std::ops::RangeInclusive;
+
+use crate::{FontGroup, FontStyle, Script};
+
 /// `NOTO_SANS` is a static array of unhinted OTF files for sans serif fonts (Google Noto fonts).
 ///
 /// These fonts are pulled from the
@@ -18,8 +22,17 @@
 /// implementation detail; do not depend on the exact length or order.
 // __PLACEHOLDER_NOTO_SERIF__
 
-/// `NOTO_REST` is a static array of unhinted OTF files for fonts that are neither sans serif nor 
-/// serif (Google Noto fonts).
+/// `NOTO_MONO` is a static array of unhinted OTF files for monospace fonts (Google Noto fonts).
+///
+/// These fonts are pulled from the
+/// [Noto Fonts GitHub repository](https://github.com/notofonts/notofonts.github.io.git)
+/// and embedded directly in the binary using [`include_bytes!`].
+/// The array's length and file order depend on the included files and are an implementation
+/// detail; do not rely on the exact length or order.
+// __PLACEHOLDER_NOTO_MONO__
+
+/// `NOTO_REST` is a static array of unhinted OTF files for fonts that are neither sans serif, serif 
+/// nor monospace (Google Noto fonts).
 ///
 /// These fonts are pulled from the
 /// [Noto Fonts GitHub repository](https://github.com/notofonts/notofonts.github.io.git)
@@ -28,6 +41,133 @@
 /// implementation detail; do not rely on the exact length or order.
 // __PLACEHOLDER_NOTO_REST__
 
+/// Characters the fonts were subset to at build time, [`None`] if they are embedded completely.
+///
+/// Characters outside of these ranges are removed from the fonts, see the module documentation.
+// __PLACEHOLDER_SUBSET__
+
+/// Metadata of a bundled font, read from the font file at build time.
+///
+/// List all bundled fonts via [`all`] or look them up via [`by_family`], [`by_file_name`] and
+/// [`by_script`]:
+/// ```rust
+/// # use hieroglyph::*;
+/// #
+/// for font in fonts::by_script(Script::Egyptian_Hieroglyphs) {
+///     println!("{} ({}), {} characters", font.family, font.file_name, font.char_count());
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontInfo {
+    /// Family name as reported by the font database, e.g. `"Noto Sans Egyptian Hieroglyphs"`.
+    pub family: &'static str,
+
+    /// Name of the font file in the Noto fonts repository.
+    pub file_name: &'static str,
+
+    /// The group the font is bundled in.
+    pub group: FontGroup,
+
+    /// Scripts the font has glyphs for, the script with the most characters first.
+    ///
+    /// Characters shared by many scripts, like digits and punctuation, are not counted.
+    pub scripts: &'static [Script],
+
+    /// Ranges of characters the font has a glyph for, in ascending order.
+    pub coverage: &'static [RangeInclusive<char>],
+
+    /// The style of the font.
+    pub style: FontStyle,
+
+    /// The weight of the font, `400` being regular.
+    pub weight: u16,
+
+    /// SPDX identifier of the license, `"unknown"` if the font does not name a known one.
+    pub license: &'static str,
+
+    /// Copyright notice of the font, empty if the font has none.
+    pub copyright: &'static str,
+
+    /// Text of the license, see [`licenses`](crate::licenses).
+    ///
+    /// If the license file was not archived with the font, this is the license description
+    /// and URL from the font itself.
+    pub license_text: &'static str,
+
+    /// Whether the font was reduced to [`SUBSET`], fonts that cannot be subset are embedded
+    /// completely.
+    pub subset: bool,
+
+    index: usize,
+}
+
+impl FontInfo {
+    /// Returns the font file.
+    pub fn data(&self) -> &'static [u8] {
+        self.group.fonts()[self.index]
+    }
+
+    /// Returns the number of characters the font has a glyph for.
+    pub fn char_count(&self) -> usize {
+        crate::report::char_count(self.coverage)
+    }
+
+    /// Returns `true` if the font has a glyph for `c`.
+    pub fn covers(&self, c: char) -> bool {
+        crate::report::ranges_contain(self.coverage, c)
+    }
+}
+
+/// Returns the metadata of all bundled fonts, grouped like the arrays above.
+pub fn all() -> &'static [FontInfo] {
+    &FONT_INFOS
+}
+
+/// Returns the bundled font of the given family, e.g. `"Noto Sans Egyptian Hieroglyphs"`.
+pub fn by_family(family: &str) -> Option<&'static FontInfo> {
+    FONT_INFOS.iter().find(|font| font.family == family)
+}
+
+/// Returns the bundled font with the given file name, e.g. `"NotoSans-Regular.otf"`.
+pub fn by_file_name(file_name: &str) -> Option<&'static FontInfo> {
+    FONT_INFOS.iter().find(|font| font.file_name == file_name)
+}
+
+/// Returns the bundled fonts with glyphs for the given script.
+pub fn by_script(script: Script) -> impl Iterator<Item = &'static FontInfo> {
+    FONT_INFOS.iter().filter(move |font| font.scripts.contains(&script))
+}
+
+// __PLACEHOLDER_LICENSE_TEXTS__
+
+// __PLACEHOLDER_FONT_INFOS__
+
+#[cfg(test)]
+#[test]
+fn font_infos_describe_fonts() {
+    let groups = [&NOTO_SANS[..], &NOTO_SERIF, &NOTO_MONO, &NOTO_REST];
+    assert_eq!(all().len(), groups.iter().map(|fonts| fonts.len()).sum::<usize>());
+
+    for font in all() {
+        assert_eq!(by_family(font.family), Some(font));
+        assert_eq!(by_file_name(font.file_name), Some(font));
+        assert!(font.group.fonts().contains(&font.data()));
+        if let Some(range) = font.coverage.first() {
+            assert!(font.covers(*range.start()));
+        }
+        for &script in font.scripts {
+            assert!(by_script(script).any(|other| other == font));
+        }
+    }
+
+    let font = by_file_name(all()[0].file_name).unwrap();
+    let mut font_db = cosmic_text::fontdb::Database::new();
+    font_db.load_font_data(font.data().to_vec());
+    let face = font_db.faces().next().unwrap();
+    assert_eq!(face.families[0].0, font.family);
+    assert_eq!(by_family("Not A Bundled Family"), None);
+}
+
 #[cfg(test)]
 #[test]
 #[rustfmt::skip]
@@ -36,5 +176,16 @@ fn fonts_are_valid() {
 
     NOTO_SANS.iter().map(|this| parse(this, 0)).collect::<Result<Vec<_>, _>>().unwrap();
     NOTO_SERIF.iter().map(|this| parse(this, 0)).collect::<Result<Vec<_>, _>>().unwrap();
+    NOTO_MONO.iter().map(|this| parse(this, 0)).collect::<Result<Vec<_>, _>>().unwrap();
     NOTO_REST.iter().map(|this| parse(this, 0)).collect::<Result<Vec<_>, _>>().unwrap();
+
+    // subset fonts only map characters of the subset
+    let Some(subset) = SUBSET else { return };
+    for font in all().iter().filter(|font| font.subset) {
+        let face = parse(font.data(), 0).unwrap();
+        for c in font.coverage.iter().flat_map(|range| range.clone()) {
+            assert!(subset.iter().any(|range| range.contains(&c)), "{c:?} in {}", font.file_name);
+            assert!(face.glyph_index(c).is_some(), "{c:?} in {}", font.file_name);
+        }
+    }
 }
@@ -0,0 +1,96 @@
+//! Tracks the memory used while loading the bundled fonts.
+//!
+//! This lives in its own test binary as it replaces the global allocator.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use hieroglyph::{DrawingContext, FontGroup};
+
+struct CountingAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static LARGEST: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+            LARGEST.fetch_max(layout.size(), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Resets the counters and returns the current allocation size.
+fn start_counting() -> usize {
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    LARGEST.store(0, Ordering::Relaxed);
+    baseline
+}
+
+#[test]
+fn building_context_does_not_copy_fonts() {
+    let all_fonts = [
+        FontGroup::Sans,
+        FontGroup::Serif,
+        FontGroup::Mono,
+        FontGroup::Rest,
+    ]
+    .into_iter()
+    .flat_map(|group| group.fonts());
+    let font_bytes: usize = all_fonts.clone().map(|font| font.len()).sum();
+    let smallest_font = all_fonts.map(|font| font.len()).min().unwrap();
+
+    let baseline = start_counting();
+    let start = Instant::now();
+    let ctx = DrawingContext::new();
+    let elapsed = start.elapsed();
+    let peak = PEAK.load(Ordering::Relaxed) - baseline;
+    let largest = LARGEST.load(Ordering::Relaxed);
+
+    println!(
+        "building a context took {elapsed:?} and peaked at {peak} bytes for {font_bytes} font \
+         bytes, the largest allocation was {largest} bytes"
+    );
+    // a copy of any font would be a single allocation of its size
+    assert!(
+        largest < smallest_font,
+        "building a context allocated {largest} bytes at once, the smallest font is \
+         {smallest_font} bytes"
+    );
+    assert!(
+        peak < font_bytes / 2,
+        "building a context allocated {peak} bytes for {font_bytes} font bytes"
+    );
+
+    let baseline = start_counting();
+    let shared = DrawingContext::configure()
+        .font_database(ctx.font_database())
+        .build();
+    let shared_peak = PEAK.load(Ordering::Relaxed) - baseline;
+    let largest = LARGEST.load(Ordering::Relaxed);
+    println!("sharing the database peaked at {shared_peak} bytes");
+    assert!(
+        largest < smallest_font,
+        "sharing allocated {largest} bytes at once, the smallest font is {smallest_font} bytes"
+    );
+    assert!(
+        shared_peak < font_bytes / 2,
+        "sharing allocated {shared_peak} bytes for {font_bytes} font bytes"
+    );
+    drop(shared);
+}
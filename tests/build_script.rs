@@ -0,0 +1,13 @@
+//! Runs the tests of the build script modules, cargo doesn't run tests for build scripts.
+
+// the build script uses more of these modules than their tests do
+#![allow(dead_code)]
+
+#[path = "../src/coverage.rs"]
+mod coverage;
+#[path = "../build/meta.rs"]
+mod meta;
+#[path = "../build/selection.rs"]
+mod selection;
+#[path = "../build/subset.rs"]
+mod subset;